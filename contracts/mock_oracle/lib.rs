@@ -0,0 +1,47 @@
+#![cfg_attr(not(feature = "std"), no_std, no_main)]
+
+pub use self::mock_oracle::MockOracleRef;
+
+/// Same trait name and message as `voting::oracletraits::ReputationOracle`, so the selectors match.
+#[ink::trait_definition]
+pub trait ReputationOracle {
+    #[ink(message)]
+    fn reputation_of(&self, who: ink::primitives::AccountId) -> i32;
+}
+
+#[ink::contract]
+pub mod mock_oracle {
+    use crate::ReputationOracle;
+
+    #[ink(storage)]
+    pub struct MockOracle {
+        reputation: i32,
+    }
+
+    impl MockOracle {
+        /// Reports `reputation` for every account.
+        #[ink(constructor)]
+        pub fn new(reputation: i32) -> Self {
+            Self { reputation }
+        }
+    }
+
+    impl ReputationOracle for MockOracle {
+        #[ink(message)]
+        fn reputation_of(&self, _who: AccountId) -> i32 {
+            self.reputation
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        #[ink::test]
+        fn reports_the_configured_reputation() {
+            let oracle = MockOracle::new(80);
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            assert_eq!(oracle.reputation_of(accounts.bob), 80);
+        }
+    }
+}
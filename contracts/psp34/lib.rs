@@ -1,36 +1,208 @@
 #![cfg_attr(not(feature = "std"), no_std, no_main)]
 
 pub use self::psp34::ContractRef;
+pub use openbrush::contracts::ownable::{Ownable, OwnableError};
+pub use openbrush::contracts::psp34::{Id, PSP34Error};
 
-#[openbrush::implementation(PSP34)]
+#[openbrush::implementation(PSP34, PSP34Enumerable, Ownable)]
 #[openbrush::contract]
 pub mod psp34 {
-    use openbrush::{traits::Storage, contracts::psp34::{self, Id}};
+    use ink::storage::Mapping;
+    use openbrush::{traits::{Storage, String}, contracts::{ownable, psp34::{self, extensions::enumerable, Id}}};
 
     #[ink(storage)]
     #[derive(Default, Storage)]
     pub struct Contract {
         #[storage_field]
         psp34: psp34::Data,
+        #[storage_field]
+        enumerable: enumerable::Data,
+        #[storage_field]
+        ownable: ownable::Data,
         next_id: u8,
+        soulbound: bool,
+        next_premium_id: u16,
+        minters: Mapping<AccountId, ()>,
+    }
+
+    /// Soulbound badges can be minted and burned but never moved between accounts.
+    #[overrider(psp34::Internal)]
+    fn _before_token_transfer(
+        &mut self,
+        from: Option<&AccountId>,
+        to: Option<&AccountId>,
+        _id: &Id,
+    ) -> Result<(), PSP34Error> {
+        if self.soulbound && from.is_some() && to.is_some() {
+            return Err(PSP34Error::Custom(String::from("Soulbound")));
+        }
+        Ok(())
     }
 
     impl Contract {
         #[ink(constructor)]
         pub fn new() -> Self {
-            Self::default()
+            let mut instance = Self::default();
+            ownable::Internal::_init_with_owner(&mut instance, Self::env().caller());
+            instance
         }
 
+        #[ink(constructor)]
+        pub fn new_with_soulbound(soulbound: bool) -> Self {
+            let mut instance = Self::new();
+            instance.soulbound = soulbound;
+            instance
+        }
+
+        /// Allows or revokes `who` minting with `mint_token` alongside the owner.
         #[ink(message)]
-        pub fn mint_token(&mut self, to: AccountId) -> Result<(), PSP34Error> {
-            psp34::Internal::_mint_to(self, to, Id::U8(self.next_id))?;
-            self.next_id += 1;
+        pub fn set_minter(&mut self, who: AccountId, allowed: bool) -> Result<(), PSP34Error> {
+            if Ownable::owner(self) != Some(self.env().caller()) {
+                return Err(PSP34Error::Custom(String::from("NotOwner")));
+            }
+            if allowed {
+                self.minters.insert(who, &());
+            } else {
+                self.minters.remove(who);
+            }
             Ok(())
         }
 
+        #[ink(message)]
+        pub fn mint_token(&mut self, to: AccountId) -> Result<Id, PSP34Error> {
+            self.ensure_minter()?;
+            let id = Id::U8(self.next_id);
+            psp34::Internal::_mint_to(self, to, id.clone())?;
+            self.next_id += 1;
+            Ok(id)
+        }
+
+        /// Premium badges use `Id::U16` so they never collide with regular ones.
+        #[ink(message)]
+        pub fn mint_premium(&mut self, to: AccountId) -> Result<Id, PSP34Error> {
+            self.ensure_minter()?;
+            let id = Id::U16(self.next_premium_id);
+            psp34::Internal::_mint_to(self, to, id.clone())?;
+            self.next_premium_id += 1;
+            Ok(id)
+        }
+
+        /// Whether `mint_token` would succeed for the caller: it may mint and ids are left.
+        #[ink(message)]
+        pub fn can_mint(&self) -> bool {
+            self.next_id < u8::MAX && self.ensure_minter().is_ok()
+        }
+
         #[ink(message)]
         pub fn balance(&self, caller: AccountId) -> u32 {
-            psp34::BalancesManagerImpl::_balance_of(self, &caller)
+            psp34::BalancesManager::_balance_of(self, &caller)
+        }
+
+        /// Number of tokens minted so far, by anyone.
+        #[ink(message)]
+        pub fn total_minted(&self) -> u32 {
+            u32::from(self.next_id) + u32::from(self.next_premium_id)
+        }
+
+        #[ink(message)]
+        pub fn token_by_index(&self, owner: AccountId, index: u128) -> Option<Id> {
+            PSP34Enumerable::owners_token_by_index(self, owner, index).ok()
+        }
+
+        fn ensure_minter(&self) -> Result<(), PSP34Error> {
+            let caller = self.env().caller();
+            if Ownable::owner(self) != Some(caller) && !self.minters.contains(caller) {
+                return Err(PSP34Error::Custom(String::from("NotMinter")));
+            }
+            Ok(())
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+        use ink::env::test;
+        use ink::env::DefaultEnvironment;
+
+        fn accounts() -> test::DefaultAccounts<DefaultEnvironment> {
+            test::default_accounts::<DefaultEnvironment>()
+        }
+
+        fn set_caller(who: AccountId) {
+            test::set_caller::<DefaultEnvironment>(who);
+        }
+
+        #[ink::test]
+        fn soulbound_badges_cannot_be_transferred() {
+            let accounts = accounts();
+            let mut contract = Contract::new_with_soulbound(true);
+            let id = contract.mint_token(accounts.bob).unwrap();
+
+            set_caller(accounts.bob);
+            let result = PSP34::transfer(&mut contract, accounts.charlie, id, Vec::new());
+            assert_eq!(result, Err(PSP34Error::Custom(String::from("Soulbound"))));
+            assert_eq!(contract.balance(accounts.bob), 1);
+            assert_eq!(contract.balance(accounts.charlie), 0);
+        }
+
+        #[ink::test]
+        fn regular_badges_can_be_transferred() {
+            let accounts = accounts();
+            let mut contract = Contract::new_with_soulbound(false);
+            let id = contract.mint_token(accounts.bob).unwrap();
+
+            set_caller(accounts.bob);
+            assert_eq!(PSP34::transfer(&mut contract, accounts.charlie, id, Vec::new()), Ok(()));
+            assert_eq!(contract.balance(accounts.bob), 0);
+            assert_eq!(contract.balance(accounts.charlie), 1);
+        }
+
+        #[ink::test]
+        fn can_mint_only_for_the_owner() {
+            let accounts = accounts();
+            let contract = Contract::new();
+            assert!(contract.can_mint());
+
+            set_caller(accounts.bob);
+            assert!(!contract.can_mint());
+        }
+
+        #[ink::test]
+        fn authorized_minter_can_mint() {
+            let accounts = accounts();
+            let mut contract = Contract::new();
+            assert_eq!(contract.set_minter(accounts.bob, true), Ok(()));
+
+            set_caller(accounts.bob);
+            assert!(contract.can_mint());
+            assert_eq!(contract.mint_token(accounts.charlie), Ok(Id::U8(0)));
+            assert_eq!(contract.mint_premium(accounts.charlie), Ok(Id::U16(0)));
+            assert_eq!(contract.balance(accounts.charlie), 2);
+        }
+
+        #[ink::test]
+        fn unauthorized_account_cannot_mint() {
+            let accounts = accounts();
+            let mut contract = Contract::new();
+            contract.set_minter(accounts.bob, true).unwrap();
+            contract.set_minter(accounts.bob, false).unwrap();
+
+            let not_minter = Err(PSP34Error::Custom(String::from("NotMinter")));
+            set_caller(accounts.bob);
+            assert_eq!(contract.mint_token(accounts.bob), not_minter);
+            set_caller(accounts.charlie);
+            assert_eq!(contract.mint_premium(accounts.charlie), not_minter);
+            assert_eq!(contract.total_minted(), 0);
+        }
+
+        #[ink::test]
+        fn only_owner_sets_minters() {
+            let accounts = accounts();
+            let mut contract = Contract::new();
+
+            set_caller(accounts.bob);
+            let result = contract.set_minter(accounts.bob, true);
+            assert_eq!(result, Err(PSP34Error::Custom(String::from("NotOwner"))));
         }
     }
 }
\ No newline at end of file
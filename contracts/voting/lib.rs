@@ -1,37 +1,225 @@
 #![cfg_attr(not(feature = "std"), no_std, no_main)]
 
+pub mod oracletraits;
 pub mod votingtraits;
 
-#[cfg_attr(feature = "cargo-clippy", allow(clippy::new_without_default))]
+#[allow(clippy::new_without_default, clippy::large_enum_variant)]
 #[ink::contract]
 mod voting {
 
+    use ink::codegen::TraitCallBuilder;
+    use ink::env::call::{build_call, ExecutionInput, Selector};
+    use ink::env::hash::Keccak256;
+    use ink::env::DefaultEnvironment;
     use ink::prelude::vec::Vec;
     use psp34::psp34::ContractRef;
+    use psp34::{Id, Ownable};
+    use crate::oracletraits::ReputationOracle;
     use crate::votingtraits::Votingtraits;
+    use ink::storage::traits::StorageKey;
     use ink::storage::Mapping;
     use scale::{Decode, Encode};
 
+    /// Upper bound on the entries returned by one call to a listing query such as
+    /// `export_state` or `my_tokens`. Larger limits are clamped rather than rejected.
+    const MAX_PAGE: u32 = 64;
+
+    /// Upper bound on the accounts accepted by `add_voters`, `remove_voters` and `import_state`,
+    /// and on the members walked by one `remove_inactive` call.
+    const MAX_BATCH: usize = 50;
+
+    /// Upper bound on the vote power staking can add on top of a caller's band.
+    const MAX_STAKE_POWER: i32 = 100;
+
+    /// Time in ms a stake stays locked after it was added or lent power to a vote, so the same
+    /// funds cannot be staked, voted with and moved to another account within a block.
+    const STAKE_LOCK_MS: u64 = 86_400_000;
+
+    /// Upper bound on the checkpoints stored per voter.
+    const MAX_CHECKPOINTS: usize = 256;
+
+    /// Upper bound on the badges minted by one `claim_pending_mints` call.
+    const MAX_CLAIM: u32 = 20;
+
+    /// `(block, reputation)` recorded by `checkpoint`.
+    type Checkpoint = (BlockNumber, i32);
+
+    /// `(recovery round, proposed admin, guardian)` recorded by `recover_admin`.
+    type RecoveryConfirmation = (u32, AccountId, AccountId);
+
+    /// Band a caller needs to cast `Unlike` when `Config::restrict_downvotes` is set.
+    const MIN_DOWNVOTE_BAND: u8 = 2;
+
+    /// Bumped whenever the storage layout changes incompatibly.
+    const STORAGE_VERSION: u16 = 3;
+
+    /// Bumped whenever messages are added, so clients can detect what a deployment supports.
+    const BUILD_TAG: u32 = 7;
+
+    /// Upper bound on the members walked by `rank_of`.
+    const MAX_RANK_SCAN: u32 = 512;
+
     #[ink(event)]
     pub struct NewVoter {
         #[ink(topic)]
         voter_id: AccountId,
+        by: AccountId,
+        event_seq: u64,
     }
 
     #[ink(event)]
     pub struct RemoveVoter {
         #[ink(topic)]
         voter_id: AccountId,
+        by: AccountId,
+        event_seq: u64,
     }
 
     #[ink(event)]
     pub struct Vote {
         #[ink(topic)]
         voter_id: AccountId,
-        #[ink(topic)]
         total_votes: i32,
         #[ink(topic)]
         votation: TypeVote,  
+        event_seq: u64,
+    }
+
+    #[ink(event)]
+    pub struct BandChanged {
+        #[ink(topic)]
+        voter: AccountId,
+        old_band: i32,
+        new_band: i32,
+        event_seq: u64,
+    }
+
+    #[ink(event)]
+    pub struct ReputationAdjusted {
+        #[ink(topic)]
+        voter_id: AccountId,
+        delta: i32,
+        event_seq: u64,
+    }
+
+    #[ink(event)]
+    pub struct ReputationChanged {
+        #[ink(topic)]
+        voter_id: AccountId,
+        before: i32,
+        after: i32,
+        event_seq: u64,
+    }
+
+    #[ink(event)]
+    pub struct AdminRecovered {
+        #[ink(topic)]
+        new_admin: AccountId,
+        event_seq: u64,
+    }
+
+    #[ink(event)]
+    pub struct Flagged {
+        #[ink(topic)]
+        by: AccountId,
+        #[ink(topic)]
+        who: AccountId,
+        event_seq: u64,
+    }
+
+    // Twins of the events above without `voter_id` as a topic, emitted when
+    // `Config::private_topics` is set.
+
+    #[ink(event)]
+    pub struct PrivateNewVoter {
+        voter_id: AccountId,
+        by: AccountId,
+        event_seq: u64,
+    }
+
+    #[ink(event)]
+    pub struct PrivateRemoveVoter {
+        voter_id: AccountId,
+        by: AccountId,
+        event_seq: u64,
+    }
+
+    #[ink(event)]
+    pub struct PrivateVote {
+        voter_id: AccountId,
+        total_votes: i32,
+        #[ink(topic)]
+        votation: TypeVote,
+        event_seq: u64,
+    }
+
+    #[ink(event)]
+    pub struct PrivateBandChanged {
+        voter: AccountId,
+        old_band: i32,
+        new_band: i32,
+        event_seq: u64,
+    }
+
+    #[ink(event)]
+    pub struct PrivateReputationAdjusted {
+        voter_id: AccountId,
+        delta: i32,
+        event_seq: u64,
+    }
+
+    #[ink(event)]
+    pub struct PrivateFlagged {
+        by: AccountId,
+        who: AccountId,
+        event_seq: u64,
+    }
+
+    #[ink(event)]
+    pub struct PrivateReputationChanged {
+        voter_id: AccountId,
+        before: i32,
+        after: i32,
+        event_seq: u64,
+    }
+
+    #[ink(event)]
+    pub struct PrivateVoterMigrated {
+        old: AccountId,
+        new: AccountId,
+        event_seq: u64,
+    }
+
+    #[ink(event)]
+    pub struct NftContractChanged {
+        #[ink(topic)]
+        code_hash: Hash,
+        event_seq: u64,
+    }
+
+    #[ink(event)]
+    pub struct VoterMigrated {
+        #[ink(topic)]
+        old: AccountId,
+        #[ink(topic)]
+        new: AccountId,
+        event_seq: u64,
+    }
+
+    #[ink(event)]
+    pub struct AutoPaused {
+        block: BlockNumber,
+        votes: u32,
+        event_seq: u64,
+    }
+
+    /// Follows a `Vote` cast with `vote_with_reason`. `reason` is the hash of text kept off-chain.
+    #[ink(event)]
+    pub struct VoteReason {
+        voter_id: AccountId,
+        #[ink(topic)]
+        reason: Hash,
+        event_seq: u64,
     }
 
     #[derive(Debug)]
@@ -52,16 +240,239 @@ mod voting {
         NotVoteItSelf,
         NotIsVoter,
         NftNotMint,
+        NoCommitment,
+        CommitRevealRequired,
+        CommitRevealDisabled,
+        Overflow,
+        InvalidAdjustment,
+        TargetVoteCapReached,
+        NftInstantiationFailed,
+        VotingClosed,
+        InvalidWindow,
+        InsufficientReputation,
+        BatchTooLarge,
+        NoPendingPurge,
+        PurgeNotReady,
+        ValueMismatch,
+        TransferFailed,
+        NotACandidate,
+        OwnershipTransferFailed,
+        InsufficientHoldings,
+        DownvotesPaused,
+        InvalidStartingReputation,
+        RecipientNotVoter,
+        InsufficientStake,
+        StakeLocked,
+        TooManyCheckpoints,
+        NotGuardian,
+        InvalidGuardianThreshold,
+        AdminCannotVote,
+        MintDisabled,
+        NoVoteToAmend,
+        AmendWindowClosed,
+        VoterCapReached,
+        ReasonRequired,
+        IncompatibleConfig,
+        CooldownActive,
+        PairBlocked,
+        InvalidCost,
+        CannotDownvote,
+        CategoryCapReached,
+        NftContractLocked,
+        AccountTooNew,
+        Paused,
+        ReputationFrozen,
+        SetMinterFailed,
     }
 
     /// Definition type of vote.
     #[derive(PartialEq, Debug, Eq, Clone, Encode, Decode)]
-    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+    #[cfg_attr(
+        feature = "std",
+        derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+    )]
     pub enum TypeVote {
         Like,
         Unlike,
     }
 
+    /// A caller's latest vote on a target: its type, the signed reputation change it applied,
+    /// when it was cast and the category it was tallied under, if any.
+    #[derive(PartialEq, Debug, Eq, Clone, Encode, Decode)]
+    #[cfg_attr(
+        feature = "std",
+        derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+    )]
+    pub struct VoteReceipt {
+        pub value: TypeVote,
+        pub power: i32,
+        pub at: u64,
+        pub category: Option<u8>,
+    }
+
+    /// Rounding applied to a voter's share of `total_votes` before banding.
+    #[derive(PartialEq, Debug, Eq, Clone, Copy, Encode, Decode)]
+    #[cfg_attr(
+        feature = "std",
+        derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+    )]
+    pub enum RoundMode {
+        Floor,
+        Ceil,
+        Nearest,
+    }
+
+    /// Tunable settings. Fixed at construction unless an admin setter exists for the field.
+    #[derive(PartialEq, Debug, Eq, Clone, Encode, Decode)]
+    #[cfg_attr(
+        feature = "std",
+        derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+    )]
+    pub struct Config {
+        /// Votes must be committed with `commit_vote` and applied with `reveal_vote`.
+        pub commit_reveal: bool,
+        /// Voters may vote for themselves.
+        pub allow_self_vote: bool,
+        /// Maximum number of votes any single account can receive.
+        pub max_votes_per_target: Option<u32>,
+        /// Timestamp (ms) from which voting is open.
+        pub voting_start: u64,
+        /// Timestamp (ms) from which voting is closed.
+        pub voting_end: u64,
+        /// Emit the `Private*` event variants, which do not index `voter_id` as a topic.
+        /// Indexers can then no longer filter by account, but the account is still in the
+        /// event data, so this hides it from topic subscriptions rather than from the chain.
+        pub private_topics: bool,
+        /// Reputation at which a voter may call `moderator_flag`.
+        pub moderator_threshold: i32,
+        /// When set, each further distinct `Unlike` a target receives within this many ms
+        /// of the first one carries half the power of the previous.
+        pub downvote_damping_window: Option<u64>,
+        /// When set, a removed voter's reputation can be zeroed with `purge` this many ms
+        /// after removal. When unset it is kept indefinitely.
+        pub purge_grace_period: Option<u64>,
+        /// Contract implementing `ReputationOracle` whose figure replaces the caller's local
+        /// reputation when computing vote power. Falls back to local reputation if the call fails.
+        pub oracle: Option<AccountId>,
+        /// Mint a PSP34 badge on every vote.
+        pub mint_enabled: bool,
+        /// Only accounts added with `add_candidate` can receive votes.
+        pub candidates_only: bool,
+        pub round_mode: RoundMode,
+        /// PSP34 collection in which new voters must hold at least `min_holdings` tokens.
+        pub holdings_collection: Option<AccountId>,
+        pub min_holdings: u32,
+        /// Reputation granted to an account the first time it is added as a voter. Must not be
+        /// negative, otherwise construction fails.
+        pub starting_reputation: i32,
+        /// Badges minted through `vote_with_recipient` may only go to enabled voters.
+        pub recipient_must_be_voter: bool,
+        /// Every `stake_unit` staked adds one to the caller's vote power, up to `MAX_STAKE_POWER`.
+        /// Zero disables staking power.
+        pub stake_unit: Balance,
+        /// Mint the vote badge to the account voted for rather than the voter.
+        pub mint_to_target: bool,
+        /// A badge is minted on every `votes_per_mint`-th vote a caller casts.
+        pub votes_per_mint: u32,
+        /// Accounts that can jointly reassign the admin with `recover_admin`.
+        pub guardians: Vec<AccountId>,
+        /// Guardian confirmations needed to recover the admin. Must be a strict majority of
+        /// `guardians`, otherwise construction fails.
+        pub guardian_threshold: u32,
+        /// The admin may cast votes.
+        pub admin_can_vote: bool,
+        /// `Unlike` votes are rejected while set.
+        pub downvotes_paused: bool,
+        /// Instantiate the badge collection with transfers disabled.
+        pub soulbound_badges: bool,
+        /// A voter whose reputation an `Unlike` pushes below this floor is removed on the spot.
+        /// This happens as a side effect of someone else's vote and emits `RemoveVoter`.
+        pub auto_remove_below: Option<i32>,
+        /// How long after casting a vote the caller may change it with `amend_vote`.
+        pub amend_window_secs: u64,
+        /// Maximum number of enabled voters at any one time.
+        pub max_voters: Option<u32>,
+        /// `Unlike` votes must go through `vote_with_reason` with a reason attached. Construction
+        /// fails if `commit_reveal` is also set, as `reveal_vote` carries no reason.
+        pub require_downvote_reason: bool,
+        /// Minimum time between two votes cast by the same caller. Zero disables the cooldown.
+        pub cooldown_secs: u64,
+        /// Multiply `cooldown_secs` by the caller's band (at least 1), so stronger voters
+        /// wait longer between votes.
+        pub cooldown_scales_with_band: bool,
+        /// Only callers in band `MIN_DOWNVOTE_BAND` or above may cast `Unlike`.
+        pub restrict_downvotes: bool,
+        /// Keep `set_nft_contract` usable after the first badge has been minted.
+        pub allow_nft_contract_swap: bool,
+        /// Blocks a voter must wait after being added before casting votes.
+        pub min_blocks_after_join: BlockNumber,
+        /// Voting pauses itself once more than this many votes land in a single block.
+        /// The vote that crosses the threshold still goes through.
+        pub auto_pause_threshold: Option<u32>,
+    }
+
+    impl Default for Config {
+        fn default() -> Self {
+            Self {
+                commit_reveal: false,
+                allow_self_vote: false,
+                max_votes_per_target: None,
+                voting_start: 0,
+                voting_end: u64::MAX,
+                private_topics: false,
+                moderator_threshold: i32::MAX,
+                downvote_damping_window: None,
+                purge_grace_period: None,
+                oracle: None,
+                mint_enabled: true,
+                candidates_only: false,
+                round_mode: RoundMode::Floor,
+                holdings_collection: None,
+                min_holdings: 0,
+                starting_reputation: 0,
+                recipient_must_be_voter: false,
+                stake_unit: 0,
+                mint_to_target: false,
+                votes_per_mint: 1,
+                guardians: Vec::new(),
+                guardian_threshold: 0,
+                admin_can_vote: true,
+                downvotes_paused: false,
+                soulbound_badges: false,
+                auto_remove_below: None,
+                amend_window_secs: 0,
+                max_voters: None,
+                require_downvote_reason: false,
+                cooldown_secs: 0,
+                cooldown_scales_with_band: false,
+                restrict_downvotes: false,
+                allow_nft_contract_swap: false,
+                min_blocks_after_join: 0,
+                auto_pause_threshold: None,
+            }
+        }
+    }
+
+    /// Outcome predicted by `simulate_vote`.
+    #[derive(PartialEq, Debug, Eq, Encode, Decode)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+    pub struct SimResult {
+        /// Target's reputation after the vote; unchanged when `error` is set.
+        pub new_reputation: i32,
+        pub would_mint: bool,
+        pub error: Option<Error>,
+    }
+
+    /// Aggregate counters returned by `stats`.
+    #[derive(PartialEq, Debug, Eq, Clone, Encode, Decode)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+    pub struct Stats {
+        pub voter_count: u32,
+        pub total_votes: i32,
+        pub total_reputation: i32,
+        pub nfts_minted: u32,
+    }
+
     #[ink(storage)]
     pub struct Voting {
         admin: Admin,
@@ -69,11 +480,83 @@ mod voting {
         enabled_voters: Mapping<AccountId, ()>,
         total_votes: i32,
         contract: ContractRef,
+        config: Config,
+        commitments: Mapping<AccountId, [u8; 32]>,
+        voter_count: u32,
+        total_reputation: i32,
+        nfts_minted: u32,
+        votes_received: Mapping<AccountId, u32>,
+        members: Mapping<u32, AccountId>,
+        member_index: Mapping<AccountId, u32>,
+        member_count: u32,
+        last_active: Mapping<AccountId, u64>,
+        flagged: Mapping<AccountId, ()>,
+        downvote_windows: Mapping<AccountId, (u64, u32)>,
+        recent_downvoters: Mapping<(AccountId, AccountId), u64>,
+        pending_purge: Mapping<AccountId, u64>,
+        candidates: Mapping<AccountId, ()>,
+        likes_received: Mapping<AccountId, u32>,
+        unlikes_received: Mapping<AccountId, u32>,
+        event_seq: u64,
+        stakes: Mapping<AccountId, Balance>,
+        stake_locked_at: Mapping<AccountId, u64>,
+        last_vote_at: Mapping<AccountId, u64>,
+        checkpoints: Mapping<AccountId, Vec<Checkpoint>>,
+        votes_cast: Mapping<AccountId, u32>,
+        last_minted: Mapping<AccountId, Id>,
+        recovery_round: u32,
+        recovery_confirmations: Mapping<RecoveryConfirmation, ()>,
+        recovery_votes: Mapping<(u32, AccountId), u32>,
+        badges_minted: Mapping<AccountId, u32>,
+        receipts: Mapping<(AccountId, AccountId), VoteReceipt>,
+        category_votes: Mapping<(AccountId, u8), i32>,
+        distinct_voters: Mapping<AccountId, u32>,
+        /// Share of `total_reputation` held by currently enabled voters.
+        enabled_reputation: i64,
+        vote_mints: u32,
+        blocked_pairs: Mapping<(AccountId, AccountId), ()>,
+        last_global_vote: u64,
+        category_caps: Mapping<u8, u32>,
+        category_votes_cast: Mapping<(AccountId, u8), u32>,
+        nft_code_hash: Hash,
+        first_mint_done: bool,
+        join_block: Mapping<AccountId, BlockNumber>,
+        paused: bool,
+        votes_in_block: (BlockNumber, u32),
+        frozen: Mapping<AccountId, ()>,
     }
 
     impl Voting {
         #[ink(constructor)]
-        pub fn new(admin: AccountId, contract_code_hash: Hash) -> Self {
+        pub fn new(admin: AccountId, contract_code_hash: Hash) -> Result<Self, Error> {
+            Self::new_with_config(admin, contract_code_hash, Config::default())
+        }
+
+        #[ink(constructor)]
+        pub fn new_with_config(admin: AccountId, contract_code_hash: Hash, config: Config) -> Result<Self, Error> {
+            let guardians = config.guardians.len();
+            if guardians > 0 && config.guardian_threshold as usize <= guardians / 2 {
+                return Err(Error::InvalidGuardianThreshold);
+            }
+            if config.starting_reputation < 0 {
+                return Err(Error::InvalidStartingReputation);
+            }
+            if config.require_downvote_reason && config.commit_reveal {
+                return Err(Error::IncompatibleConfig);
+            }
+
+            let contract = ContractRef::new_with_soulbound(config.soulbound_badges)
+                .code_hash(contract_code_hash)
+                .endowment(0)
+                .salt_bytes(Vec::new()) // Sequence of bytes
+                .try_instantiate()
+                .map_err(|_| Error::NftInstantiationFailed)?
+                .map_err(|_| Error::NftInstantiationFailed)?;
+            Ok(Self::with_contract(admin, contract, contract_code_hash, config))
+        }
+
+        /// Storage for a new instance around an already instantiated badge collection.
+        fn with_contract(admin: AccountId, contract: ContractRef, contract_code_hash: Hash, config: Config) -> Self {
             let now = Self::env().block_timestamp();
             Self {
                 admin: Admin {
@@ -83,12 +566,50 @@ mod voting {
                 votes: Mapping::default(),
                 enabled_voters: Mapping::default(),
                 total_votes: 0,
-                contract: ContractRef::new()
-                    .code_hash(contract_code_hash)
-                    .endowment(0)
-                    .salt_bytes(Vec::new()) // Sequence of bytes
-                    .instantiate(),
-
+                contract,
+                config,
+                commitments: Mapping::default(),
+                voter_count: 0,
+                total_reputation: 0,
+                nfts_minted: 0,
+                votes_received: Mapping::default(),
+                members: Mapping::default(),
+                member_index: Mapping::default(),
+                member_count: 0,
+                last_active: Mapping::default(),
+                flagged: Mapping::default(),
+                downvote_windows: Mapping::default(),
+                recent_downvoters: Mapping::default(),
+                pending_purge: Mapping::default(),
+                candidates: Mapping::default(),
+                likes_received: Mapping::default(),
+                unlikes_received: Mapping::default(),
+                event_seq: 0,
+                stakes: Mapping::default(),
+                stake_locked_at: Mapping::default(),
+                last_vote_at: Mapping::default(),
+                checkpoints: Mapping::default(),
+                votes_cast: Mapping::default(),
+                last_minted: Mapping::default(),
+                recovery_round: 0,
+                recovery_confirmations: Mapping::default(),
+                recovery_votes: Mapping::default(),
+                badges_minted: Mapping::default(),
+                receipts: Mapping::default(),
+                category_votes: Mapping::default(),
+                distinct_voters: Mapping::default(),
+                enabled_reputation: 0,
+                vote_mints: 0,
+                blocked_pairs: Mapping::default(),
+                last_global_vote: 0,
+                category_caps: Mapping::default(),
+                category_votes_cast: Mapping::default(),
+                nft_code_hash: contract_code_hash,
+                first_mint_done: false,
+                join_block: Mapping::default(),
+                paused: false,
+                votes_in_block: (0, 0),
+                frozen: Mapping::default(),
             }
         }
 
@@ -100,10 +621,18 @@ mod voting {
             if self.enabled_voters.contains(voter_id) {
                 return Err(Error::VoterAlreadyExists);
             }
+            if let Some(max_voters) = self.config.max_voters {
+                if self.voter_count >= max_voters {
+                    return Err(Error::VoterCapReached);
+                }
+            }
+            if let Some(collection) = self.config.holdings_collection {
+                if self.holdings_of(collection, voter_id) < self.config.min_holdings {
+                    return Err(Error::InsufficientHoldings);
+                }
+            }
 
-            self.enabled_voters.insert(voter_id, &());
-            self.env().emit_event(NewVoter { voter_id });
-            Ok(())
+            self.enable_voter(voter_id)
         }
 
         #[ink(message)]
@@ -115,99 +644,4017 @@ mod voting {
                 return Err(Error::VoterNotExist);
             }
 
-            self.enabled_voters.remove(voter_id);
-            self.env().emit_event(RemoveVoter { voter_id });
+            self.disable_voter(voter_id);
             Ok(())
         }
 
+        /// Moves `old`'s reputation and membership to `new` after a key rotation. Any
+        /// reputation `new` kept from an earlier membership is added to it.
+        ///
+        /// The vote cooldown, freeze, flag, candidacy and vote and badge counters follow the
+        /// key, so a rotation neither lifts a restriction nor re-mints earned badges. Receipts
+        /// and category tallies are keyed by pair or category and cannot be enumerated, so
+        /// `new` cannot amend `old`'s votes. Stakes and minted badges stay with `old`, which
+        /// holds them, and a downvote damping window simply lapses.
         #[ink(message)]
-        pub fn vote(&mut self, voter_id: AccountId, value: TypeVote) -> Result<(), Error> {
-            if !self.enabled_voters.contains(self.env().caller()) {
-                return Err(Error::NotIsVoter);
+        pub fn migrate_voter(&mut self, old: AccountId, new: AccountId) -> Result<(), Error> {
+            if self.env().caller() != self.admin.address {
+                return Err(Error::NotIsAdmin);
             }
-            if !self.enabled_voters.contains(voter_id) {
+            if !self.enabled_voters.contains(old) {
                 return Err(Error::VoterNotExist);
             }
-            if self.env().caller() == voter_id {
-                return Err(Error::NotVoteItSelf);
+            if self.enabled_voters.contains(new) {
+                return Err(Error::VoterAlreadyExists);
             }
 
-            let caller = self.env().caller();
-            let caller_votes = self.votes.get(caller).unwrap_or(0);
-            let power = self.power_of_vote(caller_votes);
+            let moved = self.votes.get(old).unwrap_or(0);
+            let existing = self.votes.get(new).unwrap_or(0);
+            let after = existing.checked_add(moved).ok_or(Error::Overflow)?;
+            self.set_reputation(old, 0);
+            self.set_reputation(new, after);
+            self.enabled_voters.remove(old);
+            self.enabled_voters.insert(new, &());
+            self.enabled_reputation += i64::from(after);
+            self.track_member(new);
+            self.pending_purge.remove(new);
+            if let Some(joined) = self.join_block.take(old) {
+                self.join_block.insert(new, &joined);
+            }
+            self.last_active.insert(new, &self.env().block_timestamp());
+            if let Some(last) = self.last_vote_at.take(old) {
+                let last = last.max(self.last_vote_at.get(new).unwrap_or(0));
+                self.last_vote_at.insert(new, &last);
+            }
+            Self::move_flag(&mut self.frozen, old, new);
+            Self::move_flag(&mut self.flagged, old, new);
+            Self::move_flag(&mut self.candidates, old, new);
+            Self::move_count(&mut self.votes_cast, old, new)?;
+            Self::move_count(&mut self.badges_minted, old, new)?;
+            Self::move_count(&mut self.votes_received, old, new)?;
+            Self::move_count(&mut self.likes_received, old, new)?;
+            Self::move_count(&mut self.unlikes_received, old, new)?;
+            Self::move_count(&mut self.distinct_voters, old, new)?;
+            self.emit_voter_migrated(old, new);
+            Ok(())
+        }
 
-            let voter_votes = self.votes.get(voter_id).unwrap_or(0);
+        #[ink(message)]
+        pub fn add_voters(&mut self, voter_ids: Vec<AccountId>) -> Result<(), Error> {
+            if voter_ids.len() > MAX_BATCH {
+                return Err(Error::BatchTooLarge);
+            }
+            for voter_id in voter_ids {
+                self.add_voter(voter_id)?;
+            }
+            Ok(())
+        }
 
-            if value == TypeVote::Like {
-                self.votes.insert(voter_id, &(voter_votes + power));
-            } else {
-                self.votes.insert(voter_id, &(voter_votes - power));
+        #[ink(message)]
+        pub fn remove_voters(&mut self, voter_ids: Vec<AccountId>) -> Result<(), Error> {
+            if voter_ids.len() > MAX_BATCH {
+                return Err(Error::BatchTooLarge);
             }
-            
-            let resultmint = self.contract.mint_token(caller);
+            for voter_id in voter_ids {
+                self.remove_voter(voter_id)?;
+            }
+            Ok(())
+        }
 
-            if resultmint.is_err() {
-                return Err(Error::NftNotMint);
+        #[ink(message)]
+        pub fn add_candidate(&mut self, candidate: AccountId) -> Result<(), Error> {
+            if self.env().caller() != self.admin.address {
+                return Err(Error::NotIsAdmin);
             }
+            self.candidates.insert(candidate, &());
+            Ok(())
+        }
 
-            if power == 0 {
-                self.total_votes += 1;
-            } else {
-                self.total_votes += power;
+        #[ink(message)]
+        pub fn remove_candidate(&mut self, candidate: AccountId) -> Result<(), Error> {
+            if self.env().caller() != self.admin.address {
+                return Err(Error::NotIsAdmin);
+            }
+            if !self.candidates.contains(candidate) {
+                return Err(Error::NotACandidate);
+            }
+            self.candidates.remove(candidate);
+            Ok(())
+        }
+
+        /// Hands both the Voting admin role and ownership of the PSP34 contract to
+        /// `new_admin`. If the PSP34 call fails the whole message reverts.
+        ///
+        /// This contract registers itself as a minter first so votes keep minting badges.
+        #[ink(message)]
+        pub fn transfer_all_ownership(&mut self, new_admin: AccountId) -> Result<(), Error> {
+            if self.env().caller() != self.admin.address {
+                return Err(Error::NotIsAdmin);
             }
 
-            self.env().emit_event(Vote { voter_id, total_votes: self.total_votes, votation: value});
+            self.contract
+                .set_minter(self.env().account_id(), true)
+                .map_err(|_| Error::OwnershipTransferFailed)?;
+            self.contract
+                .transfer_ownership(Some(new_admin))
+                .map_err(|_| Error::OwnershipTransferFailed)?;
+            self.admin = Admin {
+                address: new_admin,
+                modified_date: self.env().block_timestamp(),
+            };
             Ok(())
         }
 
+        /// Confirms `new_admin` as the replacement admin. Once `guardian_threshold`
+        /// guardians confirm the same account it becomes admin and all pending
+        /// confirmations are discarded.
         #[ink(message)]
-        pub fn get_reputation(&self, voter_id: AccountId) -> Result<i32, Error> {
-            if self.env().caller() != voter_id {
-                return Err(Error::MustBeItSelf);
+        pub fn recover_admin(&mut self, new_admin: AccountId) -> Result<(), Error> {
+            let caller = self.env().caller();
+            if !self.config.guardians.contains(&caller) {
+                return Err(Error::NotGuardian);
             }
-            if !self.enabled_voters.contains(voter_id) {
-                return Err(Error::VoterNotExist);
+
+            let round = self.recovery_round;
+            if self.recovery_confirmations.contains((round, new_admin, caller)) {
+                return Ok(());
             }
-            Ok(self.votes.get(voter_id).unwrap_or(0))
+            self.recovery_confirmations.insert((round, new_admin, caller), &());
+            let confirmations = self.recovery_votes.get((round, new_admin)).unwrap_or(0) + 1;
+            self.recovery_votes.insert((round, new_admin), &confirmations);
+
+            if confirmations >= self.config.guardian_threshold.max(1) {
+                self.admin = Admin {
+                    address: new_admin,
+                    modified_date: self.env().block_timestamp(),
+                };
+                self.recovery_round += 1;
+                let event_seq = self.next_event_seq();
+                self.env().emit_event(AdminRecovered { new_admin, event_seq });
+            }
+            Ok(())
         }
 
+        /// Removes every voter whose last vote or registration predates `cutoff`, among the
+        /// members at positions `start..start + limit`. `limit` is clamped to `MAX_BATCH`,
+        /// so large memberships are swept over several calls.
         #[ink(message)]
-        pub fn get_balance(&self, voter_id: AccountId) -> Result<u32, Error> {
-            if self.env().caller() != voter_id {
-                return Err(Error::MustBeItSelf);
+        pub fn remove_inactive(&mut self, cutoff: u64, start: u32, limit: u32) -> Result<u32, Error> {
+            if self.env().caller() != self.admin.address {
+                return Err(Error::NotIsAdmin);
             }
-            if !self.enabled_voters.contains(voter_id) {
-                return Err(Error::VoterNotExist);
+
+            let mut removed = 0;
+            let end = start.saturating_add(limit.min(MAX_BATCH as u32)).min(self.member_count);
+            for index in start..end {
+                if let Some(voter_id) = self.members.get(index) {
+                    if self.enabled_voters.contains(voter_id)
+                        && self.last_active.get(voter_id).unwrap_or(0) < cutoff
+                    {
+                        self.disable_voter(voter_id);
+                        removed += 1;
+                    }
+                }
             }
-            Ok(self.contract.balance(voter_id))            
+            Ok(removed)
         }
 
-        fn power_of_vote(&mut self, votes: i32) -> i32 {
-            if self.total_votes == 0 {
-                1
-            } else {
-                let power = (votes * 100)/self.total_votes;
-                match power {
-                    i if i < 0 => 0,
-                    0...33 => 1,
-                    34...66 => 2,
-                    _ => 3
+        /// Zeroes the reputation of a removed voter once their grace period has passed.
+        /// Anyone may call this.
+        #[ink(message)]
+        pub fn purge(&mut self, who: AccountId) -> Result<(), Error> {
+            let eligible_at = self.pending_purge.get(who).ok_or(Error::NoPendingPurge)?;
+            if self.env().block_timestamp() < eligible_at {
+                return Err(Error::PurgeNotReady);
+            }
+
+            self.pending_purge.remove(who);
+            self.total_reputation -= self.votes.get(who).unwrap_or(0);
+            self.set_reputation(who, 0);
+            Ok(())
+        }
+
+        /// Pages through every account ever registered as `(account, reputation, is_enabled)`.
+        #[ink(message)]
+        pub fn export_state(&self, start: u32, limit: u32) -> Result<Vec<(AccountId, i32, bool)>, Error> {
+            if self.env().caller() != self.admin.address {
+                return Err(Error::NotIsAdmin);
+            }
+
+            let end = start.saturating_add(limit.min(MAX_PAGE)).min(self.member_count);
+            Ok((start..end)
+                .filter_map(|index| self.members.get(index))
+                .map(|who| (who, self.votes.get(who).unwrap_or(0), self.enabled_voters.contains(who)))
+                .collect())
+        }
+
+        /// Restores entries produced by `export_state`.
+        ///
+        /// `total_votes` is not part of the export, so power bands are recomputed from
+        /// scratch as new votes arrive after a restore.
+        #[ink(message)]
+        pub fn import_state(&mut self, entries: Vec<(AccountId, i32, bool)>) -> Result<(), Error> {
+            if self.env().caller() != self.admin.address {
+                return Err(Error::NotIsAdmin);
+            }
+            if entries.len() > MAX_BATCH {
+                return Err(Error::BatchTooLarge);
+            }
+
+            for (who, reputation, enabled) in entries {
+                let current = self.votes.get(who).unwrap_or(0);
+                self.total_reputation = reputation
+                    .checked_sub(current)
+                    .and_then(|delta| self.total_reputation.checked_add(delta))
+                    .ok_or(Error::Overflow)?;
+                self.set_reputation(who, reputation);
+                self.track_member(who);
+
+                match (enabled, self.enabled_voters.contains(who)) {
+                    (true, false) => self.enable_voter(who)?,
+                    (false, true) => self.disable_voter(who),
+                    _ => {}
                 }
             }
+            Ok(())
         }
-    }
 
-    impl Votingtraits for Voting {
-    
+        /// Applies `delta` directly to `voter_id`'s reputation to correct mistaken votes.
         #[ink(message)]
-        fn vote(&mut self, voter_id: AccountId, value: TypeVote) -> Result<(), Error> {        
-            self.vote(voter_id, value).unwrap();
+        pub fn admin_adjust_reputation(&mut self, voter_id: AccountId, delta: i32) -> Result<(), Error> {
+            if self.env().caller() != self.admin.address {
+                return Err(Error::NotIsAdmin);
+            }
+
+            self.adjust_reputation(voter_id, delta)?;
+            self.adjust_total_votes(delta)
+        }
+
+        /// Batch form of `admin_adjust_reputation`; `total_votes` is updated once with the net delta.
+        #[ink(message)]
+        pub fn admin_adjust_batch(&mut self, adjustments: Vec<(AccountId, i32)>) -> Result<(), Error> {
+            if self.env().caller() != self.admin.address {
+                return Err(Error::NotIsAdmin);
+            }
+            if adjustments.len() > MAX_BATCH {
+                return Err(Error::BatchTooLarge);
+            }
+
+            let mut net_delta: i32 = 0;
+            for (voter_id, delta) in adjustments {
+                self.adjust_reputation(voter_id, delta)?;
+                net_delta = net_delta.checked_add(delta).ok_or(Error::Overflow)?;
+            }
+            self.adjust_total_votes(net_delta)
+        }
+
+        #[ink(message)]
+        pub fn set_max_votes_per_target(&mut self, max_votes: Option<u32>) -> Result<(), Error> {
+            if self.env().caller() != self.admin.address {
+                return Err(Error::NotIsAdmin);
+            }
+            self.config.max_votes_per_target = max_votes;
             Ok(())
         }
 
+        /// Votes cast while minting is disabled still count towards badges, which the voter
+        /// can collect with `claim_pending_mints` once it is enabled again.
         #[ink(message)]
-        fn get_reputation(&self, voter_id: AccountId) -> Result<i32, Error> {
-            Ok(self.get_reputation(voter_id).unwrap_or(0))
+        pub fn set_mint_enabled(&mut self, enabled: bool) -> Result<(), Error> {
+            if self.env().caller() != self.admin.address {
+                return Err(Error::NotIsAdmin);
+            }
+            self.config.mint_enabled = enabled;
+            Ok(())
+        }
+
+        /// Badges already earned are recomputed under the new ratio by `claim_pending_mints`.
+        #[ink(message)]
+        pub fn set_votes_per_mint(&mut self, votes_per_mint: u32) -> Result<(), Error> {
+            if self.env().caller() != self.admin.address {
+                return Err(Error::NotIsAdmin);
+            }
+            self.config.votes_per_mint = votes_per_mint;
+            Ok(())
+        }
+
+        /// Limits how many `vote_categorized` votes each caller may cast under `category`.
+        #[ink(message)]
+        pub fn set_category_cap(&mut self, category: u8, cap: Option<u32>) -> Result<(), Error> {
+            if self.env().caller() != self.admin.address {
+                return Err(Error::NotIsAdmin);
+            }
+            if let Some(cap) = cap {
+                self.category_caps.insert(category, &cap);
+            } else {
+                self.category_caps.remove(category);
+            }
+            Ok(())
+        }
+
+        /// Stops votes from changing `who`'s reputation. Votes for `who` are still accepted,
+        /// counted and minted for, except `vote_quadratic` which would charge for nothing.
+        #[ink(message)]
+        pub fn freeze_reputation(&mut self, who: AccountId) -> Result<(), Error> {
+            if self.env().caller() != self.admin.address {
+                return Err(Error::NotIsAdmin);
+            }
+            self.frozen.insert(who, &());
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn unfreeze_reputation(&mut self, who: AccountId) -> Result<(), Error> {
+            if self.env().caller() != self.admin.address {
+                return Err(Error::NotIsAdmin);
+            }
+            self.frozen.remove(who);
+            Ok(())
+        }
+
+        /// Lets `who` mint badges in the current collection directly, alongside this contract.
+        /// Only works while this contract owns the collection; after `transfer_all_ownership`
+        /// the new owner manages minters on the collection itself.
+        #[ink(message)]
+        pub fn set_nft_minter(&mut self, who: AccountId, allowed: bool) -> Result<(), Error> {
+            if self.env().caller() != self.admin.address {
+                return Err(Error::NotIsAdmin);
+            }
+            self.contract.set_minter(who, allowed).map_err(|_| Error::SetMinterFailed)
+        }
+
+        /// Lifts a pause set by `auto_pause_threshold`.
+        #[ink(message)]
+        pub fn unpause(&mut self) -> Result<(), Error> {
+            if self.env().caller() != self.admin.address {
+                return Err(Error::NotIsAdmin);
+            }
+            self.paused = false;
+            Ok(())
+        }
+
+        /// Stops `a` and `b` from voting for each other, in either direction.
+        #[ink(message)]
+        pub fn block_pair(&mut self, a: AccountId, b: AccountId) -> Result<(), Error> {
+            if self.env().caller() != self.admin.address {
+                return Err(Error::NotIsAdmin);
+            }
+            self.blocked_pairs.insert((a, b), &());
+            self.blocked_pairs.insert((b, a), &());
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn unblock_pair(&mut self, a: AccountId, b: AccountId) -> Result<(), Error> {
+            if self.env().caller() != self.admin.address {
+                return Err(Error::NotIsAdmin);
+            }
+            self.blocked_pairs.remove((a, b));
+            self.blocked_pairs.remove((b, a));
+            Ok(())
+        }
+
+        /// Voters already enabled are kept if the new cap is below the current count;
+        /// `add_voter` fails until enough of them are removed.
+        #[ink(message)]
+        pub fn set_max_voters(&mut self, max_voters: Option<u32>) -> Result<(), Error> {
+            if self.env().caller() != self.admin.address {
+                return Err(Error::NotIsAdmin);
+            }
+            self.config.max_voters = max_voters;
+            Ok(())
+        }
+
+        /// While set, `Unlike` votes are rejected and `Like` votes still go through.
+        #[ink(message)]
+        pub fn set_downvotes_paused(&mut self, paused: bool) -> Result<(), Error> {
+            if self.env().caller() != self.admin.address {
+                return Err(Error::NotIsAdmin);
+            }
+            self.config.downvotes_paused = paused;
+            Ok(())
+        }
+
+        /// Pushes `voting_end` later; the window can only be extended, never shortened.
+        #[ink(message)]
+        pub fn extend_voting(&mut self, voting_end: u64) -> Result<(), Error> {
+            if self.env().caller() != self.admin.address {
+                return Err(Error::NotIsAdmin);
+            }
+            if voting_end < self.config.voting_end {
+                return Err(Error::InvalidWindow);
+            }
+            self.config.voting_end = voting_end;
+            Ok(())
+        }
+
+        /// Instantiates a fresh PSP34 contract and mints all further badges there. Locked once
+        /// a badge has been minted unless `Config::allow_nft_contract_swap` is set. The current
+        /// collection is kept if instantiation fails.
+        #[ink(message)]
+        pub fn set_nft_contract(&mut self, code_hash: Hash, salt: Vec<u8>) -> Result<(), Error> {
+            if self.env().caller() != self.admin.address {
+                return Err(Error::NotIsAdmin);
+            }
+            if self.first_mint_done && !self.config.allow_nft_contract_swap {
+                return Err(Error::NftContractLocked);
+            }
+
+            self.contract = ContractRef::new_with_soulbound(self.config.soulbound_badges)
+                .code_hash(code_hash)
+                .endowment(0)
+                .salt_bytes(salt)
+                .try_instantiate()
+                .map_err(|_| Error::NftInstantiationFailed)?
+                .map_err(|_| Error::NftInstantiationFailed)?;
+            self.nft_code_hash = code_hash;
+            let event_seq = self.next_event_seq();
+            self.env().emit_event(NftContractChanged { code_hash, event_seq });
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn vote(&mut self, voter_id: AccountId, value: TypeVote) -> Result<(), Error> {
+            if self.config.commit_reveal {
+                return Err(Error::CommitRevealRequired);
+            }
+            self.apply_vote(voter_id, value, None, None, None)
+        }
+
+        /// Like `vote`, but mints the earned badge to `mint_to` instead of the caller.
+        #[ink(message)]
+        pub fn vote_with_recipient(&mut self, voter_id: AccountId, value: TypeVote, mint_to: Option<AccountId>) -> Result<(), Error> {
+            if self.config.commit_reveal {
+                return Err(Error::CommitRevealRequired);
+            }
+            self.apply_vote(voter_id, value, mint_to, None, None)
+        }
+
+        /// Like `vote`, attaching the hash of a justification kept off-chain.
+        #[ink(message)]
+        pub fn vote_with_reason(&mut self, voter_id: AccountId, value: TypeVote, reason: Option<Hash>) -> Result<(), Error> {
+            if self.config.commit_reveal {
+                return Err(Error::CommitRevealRequired);
+            }
+            self.apply_vote(voter_id, value, None, None, reason)
+        }
+
+        /// Like `vote`, additionally tallying the reputation change under `category`.
+        #[ink(message)]
+        pub fn vote_categorized(&mut self, voter_id: AccountId, value: TypeVote, category: u8) -> Result<(), Error> {
+            if self.config.commit_reveal {
+                return Err(Error::CommitRevealRequired);
+            }
+            self.apply_vote(voter_id, value, None, Some(category), None)
+        }
+
+        /// Votes for `voter_id` and forwards the attached value to them as a tip.
+        #[ink(message, payable)]
+        pub fn vote_and_tip(&mut self, voter_id: AccountId, value: TypeVote, amount: Balance) -> Result<(), Error> {
+            if self.env().transferred_value() != amount {
+                return Err(Error::ValueMismatch);
+            }
+            self.vote(voter_id, value)?;
+            self.env().transfer(voter_id, amount).map_err(|_| Error::TransferFailed)
+        }
+
+        /// Locks the attached value as stake, raising the caller's vote power.
+        #[ink(message, payable)]
+        pub fn stake(&mut self) -> Result<(), Error> {
+            let caller = self.env().caller();
+            let staked = self.stakes.get(caller).unwrap_or(0);
+            let staked = staked.checked_add(self.env().transferred_value()).ok_or(Error::Overflow)?;
+            self.stakes.insert(caller, &staked);
+            self.stake_locked_at.insert(caller, &self.env().block_timestamp());
+            Ok(())
+        }
+
+        /// Returns `amount` of the caller's stake once `STAKE_LOCK_MS` has passed since they
+        /// last staked or voted with stake power.
+        #[ink(message)]
+        pub fn unstake(&mut self, amount: Balance) -> Result<(), Error> {
+            let caller = self.env().caller();
+            let locked_at = self.stake_locked_at.get(caller).unwrap_or(0);
+            if self.env().block_timestamp() < locked_at.saturating_add(STAKE_LOCK_MS) {
+                return Err(Error::StakeLocked);
+            }
+            let staked = self.stakes.get(caller).unwrap_or(0);
+            let remaining = staked.checked_sub(amount).ok_or(Error::InsufficientStake)?;
+            self.stakes.insert(caller, &remaining);
+            self.env().transfer(caller, amount).map_err(|_| Error::TransferFailed)
+        }
+
+        #[ink(message)]
+        pub fn stake_of(&self, who: AccountId) -> Balance {
+            self.stakes.get(who).unwrap_or(0)
+        }
+
+        /// Replaces the caller's last vote on `voter_id` with `new_value`, reverting the old
+        /// reputation change and applying one computed at the caller's current power.
+        /// `total_votes` and badges are left as they were. The target and vote-type checks of a
+        /// new vote apply, so amending to `Unlike` is refused under `require_downvote_reason`,
+        /// and so do downvote damping and `auto_remove_below`. Unavailable under commit-reveal,
+        /// where it would let a voter change their vote after seeing others revealed.
+        #[ink(message)]
+        pub fn amend_vote(&mut self, voter_id: AccountId, new_value: TypeVote) -> Result<(), Error> {
+            if self.config.commit_reveal {
+                return Err(Error::CommitRevealRequired);
+            }
+            let caller = self.env().caller();
+            self.ensure_can_vote(caller)?;
+            let receipt = self.receipts.get((caller, voter_id)).ok_or(Error::NoVoteToAmend)?;
+            let window = self.config.amend_window_secs.saturating_mul(1000);
+            if self.env().block_timestamp() >= receipt.at.saturating_add(window) {
+                return Err(Error::AmendWindowClosed);
+            }
+            self.ensure_target_allowed(caller, voter_id, &new_value, false)?;
+
+            let delta = if self.frozen.contains(voter_id) {
+                receipt.power
+            } else if new_value == TypeVote::Unlike {
+                let delta = self.effective_power(caller, TypeVote::Unlike);
+                self.damp_downvote(caller, voter_id, delta)
+            } else {
+                self.effective_power(caller, TypeVote::Like)
+            };
+            let change = delta.checked_sub(receipt.power).ok_or(Error::Overflow)?;
+            let votes = self.votes.get(voter_id).unwrap_or(0);
+            let new_votes = votes.checked_add(change).ok_or(Error::Overflow)?;
+            self.total_reputation = self.total_reputation.checked_add(change).ok_or(Error::Overflow)?;
+            self.set_reputation(voter_id, new_votes);
+
+            if receipt.value != new_value {
+                let (likes, unlikes) = self.vote_breakdown(voter_id);
+                let (likes, unlikes) = match new_value {
+                    TypeVote::Like => (likes + 1, unlikes.saturating_sub(1)),
+                    TypeVote::Unlike => (likes.saturating_sub(1), unlikes + 1),
+                };
+                self.likes_received.insert(voter_id, &likes);
+                self.unlikes_received.insert(voter_id, &unlikes);
+            }
+            if let Some(category) = receipt.category {
+                let category_votes = self.category_votes.get((voter_id, category)).unwrap_or(0);
+                self.category_votes.insert((voter_id, category), &(category_votes + change));
+            }
+            self.receipts.insert((caller, voter_id), &VoteReceipt { value: new_value, power: delta, ..receipt });
+            self.lock_stake(caller);
+            self.enforce_reputation_floor(voter_id, change, new_votes);
+            Ok(())
+        }
+
+        /// Mints to the caller any badges their votes earned under the current `votes_per_mint`
+        /// but that were never delivered, e.g. while minting was disabled. At most `MAX_CLAIM`
+        /// per call; returns how many were minted.
+        #[ink(message)]
+        pub fn claim_pending_mints(&mut self) -> Result<u32, Error> {
+            if !self.config.mint_enabled {
+                return Err(Error::MintDisabled);
+            }
+
+            let caller = self.env().caller();
+            let earned = self.votes_cast.get(caller).unwrap_or(0) / self.config.votes_per_mint.max(1);
+            let minted = self.badges_minted.get(caller).unwrap_or(0);
+            let pending = earned.saturating_sub(minted).min(MAX_CLAIM);
+
+            for _ in 0..pending {
+                let id = self.contract.mint_token(caller).map_err(|_| Error::NftNotMint)?;
+                self.last_minted.insert(caller, &id);
+                self.first_mint_done = true;
+            }
+            self.nfts_minted += pending;
+            self.badges_minted.insert(caller, &(minted + pending));
+            Ok(pending)
+        }
+
+        /// Adds `n` to `voter_id`'s reputation at a cost of `n * n` of the caller's own.
+        /// Counts as one `Like` for caps and counters; no badge is minted.
+        #[ink(message)]
+        pub fn vote_quadratic(&mut self, voter_id: AccountId, n: u32) -> Result<(), Error> {
+            let caller = self.env().caller();
+            if self.config.commit_reveal {
+                return Err(Error::CommitRevealRequired);
+            }
+            self.ensure_vote_allowed(caller, voter_id, &TypeVote::Like, false)?;
+            if self.frozen.contains(voter_id) {
+                return Err(Error::ReputationFrozen);
+            }
+            if n == 0 {
+                return Err(Error::InvalidCost);
+            }
+            let amount = i32::try_from(n).map_err(|_| Error::Overflow)?;
+            let cost = amount.checked_mul(amount).ok_or(Error::Overflow)?;
+            let caller_votes = self.votes.get(caller).unwrap_or(0);
+            if caller_votes < cost {
+                return Err(Error::InsufficientReputation);
+            }
+            let old_band = self.power_of_vote(self.votes.get(voter_id).unwrap_or(0));
+
+            // Debit first so a self-vote reads the already reduced balance.
+            self.set_reputation(caller, caller_votes - cost);
+            let target_votes = self.votes.get(voter_id).unwrap_or(0);
+            let new_votes = target_votes.checked_add(amount).ok_or(Error::Overflow)?;
+            self.set_reputation(voter_id, new_votes);
+            self.total_reputation += amount - cost;
+            self.total_votes = self.total_votes.checked_add(amount).ok_or(Error::Overflow)?;
+
+            let votes_received = self.votes_received.get(voter_id).unwrap_or(0);
+            self.votes_received.insert(voter_id, &(votes_received + 1));
+            let likes = self.likes_received.get(voter_id).unwrap_or(0);
+            self.likes_received.insert(voter_id, &(likes + 1));
+            let now = self.env().block_timestamp();
+            self.last_active.insert(caller, &now);
+            self.last_vote_at.insert(caller, &now);
+            self.last_global_vote = now;
+
+            self.emit_vote(voter_id, TypeVote::Like);
+            self.track_block_volume();
+            let new_band = self.power_of_vote(new_votes);
+            if new_band != old_band {
+                self.emit_band_changed(voter_id, old_band, new_band);
+            }
+            Ok(())
+        }
+
+        /// Spends `cost` of the caller's own reputation on a premium badge.
+        #[ink(message)]
+        pub fn redeem_reputation(&mut self, cost: i32) -> Result<Id, Error> {
+            let caller = self.env().caller();
+            if !self.enabled_voters.contains(caller) {
+                return Err(Error::NotIsVoter);
+            }
+            if cost <= 0 {
+                return Err(Error::InvalidCost);
+            }
+            let votes = self.votes.get(caller).unwrap_or(0);
+            if votes < cost {
+                return Err(Error::InsufficientReputation);
+            }
+
+            // Checked before minting: a redeem must not leave `total_votes` non-positive.
+            self.adjust_total_votes(-cost)?;
+            let id = self.contract.mint_premium(caller).map_err(|_| Error::NftNotMint)?;
+            self.set_reputation(caller, votes - cost);
+            self.total_reputation = self.total_reputation.checked_sub(cost).ok_or(Error::Overflow)?;
+            self.nfts_minted += 1;
+            self.first_mint_done = true;
+            Ok(id)
+        }
+
+        /// Stores `keccak256(SCALE(voter_id, value, salt))` as the caller's pending vote.
+        #[ink(message)]
+        pub fn commit_vote(&mut self, hash: [u8; 32]) -> Result<(), Error> {
+            if !self.config.commit_reveal {
+                return Err(Error::CommitRevealDisabled);
+            }
+            let caller = self.env().caller();
+            if !self.enabled_voters.contains(caller) {
+                return Err(Error::NotIsVoter);
+            }
+
+            self.commitments.insert(caller, &hash);
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn reveal_vote(&mut self, voter_id: AccountId, value: TypeVote, salt: [u8; 32]) -> Result<(), Error> {
+            if !self.config.commit_reveal {
+                return Err(Error::CommitRevealDisabled);
+            }
+            let caller = self.env().caller();
+            let commitment = self.commitments.get(caller).ok_or(Error::NoCommitment)?;
+            let hash = self.env().hash_encoded::<Keccak256, _>(&(voter_id, value.clone(), salt));
+            if commitment != hash {
+                return Err(Error::NoCommitment);
+            }
+
+            self.commitments.remove(caller);
+            self.apply_vote(voter_id, value, None, None, None)
+        }
+
+        /// Marks `who` for admin review. Open to voters at or above `moderator_threshold`.
+        #[ink(message)]
+        pub fn moderator_flag(&mut self, who: AccountId) -> Result<(), Error> {
+            let caller = self.env().caller();
+            if !self.enabled_voters.contains(caller) {
+                return Err(Error::NotIsVoter);
+            }
+            if self.votes.get(caller).unwrap_or(0) < self.config.moderator_threshold {
+                return Err(Error::InsufficientReputation);
+            }
+
+            self.flagged.insert(who, &());
+            self.emit_flagged(caller, who);
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn is_flagged(&self, who: AccountId) -> bool {
+            self.flagged.contains(who)
+        }
+
+        #[ink(message)]
+        pub fn get_reputation(&self, voter_id: AccountId) -> Result<i32, Error> {
+            if self.env().caller() != voter_id {
+                return Err(Error::MustBeItSelf);
+            }
+            if !self.enabled_voters.contains(voter_id) {
+                return Err(Error::VoterNotExist);
+            }
+            Ok(self.votes.get(voter_id).unwrap_or(0))
+        }
+
+        #[ink(message)]
+        pub fn get_balance(&self, voter_id: AccountId) -> Result<u32, Error> {
+            if self.env().caller() != voter_id {
+                return Err(Error::MustBeItSelf);
+            }
+            if !self.enabled_voters.contains(voter_id) {
+                return Err(Error::VoterNotExist);
+            }
+            Ok(self.contract.balance(voter_id))            
+        }
+
+        #[ink(message)]
+        pub fn config(&self) -> Config {
+            self.config.clone()
+        }
+
+        /// `(STORAGE_VERSION, BUILD_TAG)` of this deployment.
+        #[ink(message)]
+        pub fn version_info(&self) -> (u16, u32) {
+            (STORAGE_VERSION, BUILD_TAG)
+        }
+
+        #[ink(message)]
+        pub fn stats(&self) -> Stats {
+            Stats {
+                voter_count: self.voter_count,
+                total_votes: self.total_votes,
+                total_reputation: self.total_reputation,
+                nfts_minted: self.nfts_minted,
+            }
+        }
+
+        #[ink(message)]
+        pub fn is_frozen(&self, who: AccountId) -> bool {
+            self.frozen.contains(who)
+        }
+
+        #[ink(message)]
+        pub fn is_paused(&self) -> bool {
+            self.paused
+        }
+
+        #[ink(message)]
+        pub fn is_voting_open(&self) -> bool {
+            let now = self.env().block_timestamp();
+            self.config.voting_start <= now && now < self.config.voting_end
+        }
+
+        /// Power band (0-3) that `reputation` falls into given the current `total_votes`.
+        #[ink(message)]
+        pub fn band_of(&self, reputation: i32) -> u8 {
+            if self.total_votes == 0 {
+                1
+            } else {
+                let total = i64::from(self.total_votes);
+                let scaled = i64::from(reputation) * 100;
+                let quotient = scaled / total;
+                let remainder = scaled % total;
+                let power = match self.config.round_mode {
+                    RoundMode::Floor => quotient,
+                    RoundMode::Ceil if remainder > 0 => quotient + 1,
+                    RoundMode::Nearest if remainder * 2 >= total => quotient + 1,
+                    _ => quotient,
+                };
+                match power {
+                    i if i < 0 => 0,
+                    0...33 => 1,
+                    34...66 => 2,
+                    _ => 3
+                }
+            }
+        }
+
+        /// `who`'s current reputation expressed as its band, for displays that hide raw figures.
+        #[ink(message)]
+        pub fn banded_reputation(&self, who: AccountId) -> u8 {
+            self.band_of(self.votes.get(who).unwrap_or(0))
+        }
+
+        /// Pre-flight for `vote`: returns the error that would currently block the caller,
+        /// ignoring checks that depend on the target or vote type.
+        #[ink(message)]
+        pub fn can_i_vote(&self) -> Result<(), Error> {
+            let caller = self.env().caller();
+            self.ensure_can_vote(caller)?;
+            self.ensure_cooldown_elapsed(caller)
+        }
+
+        /// Dry run of `vote` for the caller, including downvote damping and the target's freeze.
+        /// `would_mint` is false whenever the badge collection cannot be reached.
+        #[ink(message)]
+        pub fn simulate_vote(&self, voter_id: AccountId, value: TypeVote) -> SimResult {
+            let caller = self.env().caller();
+            let reputation = self.votes.get(voter_id).unwrap_or(0);
+            let checked = if self.config.commit_reveal {
+                Err(Error::CommitRevealRequired)
+            } else {
+                self.ensure_vote_allowed(caller, voter_id, &value, false)
+            };
+            if let Err(error) = checked {
+                return SimResult { new_reputation: reputation, would_mint: false, error: Some(error) };
+            }
+
+            let mut delta = self.effective_power(caller, value.clone());
+            if value == TypeVote::Unlike {
+                let (_, count) = self.downvote_window(voter_id);
+                delta /= 1 << count.min(30);
+            }
+            if self.frozen.contains(voter_id) {
+                delta = 0;
+            }
+            let votes_cast = self.votes_cast.get(caller).unwrap_or(0) + 1;
+            let would_mint = self.config.mint_enabled
+                && votes_cast.is_multiple_of(self.config.votes_per_mint.max(1))
+                && matches!(self.contract.call().can_mint().try_invoke(), Ok(Ok(true)));
+            SimResult { new_reputation: reputation.saturating_add(delta), would_mint, error: None }
+        }
+
+        /// Seconds, rounded up, until `who` is past their vote cooldown; 0 if they can vote now.
+        #[ink(message)]
+        pub fn cooldown_remaining(&self, who: AccountId) -> u64 {
+            let last = match self.last_vote_at.get(who) {
+                Some(last) => last,
+                None => return 0,
+            };
+            let ready_at = last.saturating_add(self.cooldown_of(who));
+            ready_at.saturating_sub(self.env().block_timestamp()).div_ceil(1000)
+        }
+
+        /// Signed reputation change a vote of `value` cast by `caller` carries before downvote
+        /// damping and the target's freeze; see `simulate_vote` for the applied figure.
+        #[ink(message)]
+        pub fn effective_power(&self, caller: AccountId, value: TypeVote) -> i32 {
+            let power = self.power_of_vote(self.power_basis(caller)).saturating_add(self.stake_power(caller));
+            match value {
+                TypeVote::Like => power,
+                TypeVote::Unlike => -power,
+            }
+        }
+
+        /// 1-based position of `who` among enabled voters by descending reputation.
+        /// Tied voters share a rank. Only the first `MAX_RANK_SCAN` members are considered.
+        #[ink(message)]
+        pub fn rank_of(&self, who: AccountId) -> Option<u32> {
+            if !self.enabled_voters.contains(who) {
+                return None;
+            }
+
+            let reputation = self.votes.get(who).unwrap_or(0);
+            let ahead = (0..self.member_count.min(MAX_RANK_SCAN))
+                .filter_map(|index| self.members.get(index))
+                .filter(|other| self.enabled_voters.contains(other))
+                .filter(|other| self.votes.get(other).unwrap_or(0) > reputation)
+                .count() as u32;
+            Some(ahead + 1)
+        }
+
+        /// Gini coefficient of enabled voters' reputation in basis points: 0 when everyone holds
+        /// the same, approaching 10 000 when one voter holds it all. Negative reputation counts
+        /// as zero, and like `rank_of` only the first `MAX_RANK_SCAN` members are considered.
+        #[ink(message)]
+        pub fn reputation_gini(&self) -> u32 {
+            let mut reputations: Vec<i128> = (0..self.member_count.min(MAX_RANK_SCAN))
+                .filter_map(|index| self.members.get(index))
+                .filter(|who| self.enabled_voters.contains(who))
+                .map(|who| i128::from(self.votes.get(who).unwrap_or(0).max(0)))
+                .collect();
+            let n = reputations.len() as i128;
+            let total: i128 = reputations.iter().sum();
+            if n == 0 || total == 0 {
+                return 0;
+            }
+
+            // G = (2 * sum(i * x_i) - (n + 1) * sum(x)) / (n * sum(x)), x ascending, i from 1.
+            reputations.sort_unstable();
+            let weighted: i128 = reputations
+                .iter()
+                .enumerate()
+                .map(|(index, reputation)| (index as i128 + 1) * reputation)
+                .sum();
+            ((2 * weighted - (n + 1) * total) * 10_000 / (n * total)) as u32
+        }
+
+        /// Records `voter_id`'s current reputation at the current block. Only the voter or the
+        /// admin may call this, so nobody else can use up their `MAX_CHECKPOINTS` slots.
+        #[ink(message)]
+        pub fn checkpoint(&mut self, voter_id: AccountId) -> Result<(), Error> {
+            let caller = self.env().caller();
+            if caller != voter_id && caller != self.admin.address {
+                return Err(Error::MustBeItSelf);
+            }
+            let block = self.env().block_number();
+            let reputation = self.votes.get(voter_id).unwrap_or(0);
+            let mut checkpoints = self.checkpoints.get(voter_id).unwrap_or_default();
+
+            match checkpoints.last_mut() {
+                Some(last) if last.0 == block => last.1 = reputation,
+                _ => {
+                    if checkpoints.len() >= MAX_CHECKPOINTS {
+                        return Err(Error::TooManyCheckpoints);
+                    }
+                    checkpoints.push((block, reputation));
+                }
+            }
+            self.checkpoints.insert(voter_id, &checkpoints);
+            Ok(())
+        }
+
+        /// Reputation recorded by the latest checkpoint at or before `block`, or 0 if none.
+        #[ink(message)]
+        pub fn reputation_at_block(&self, voter_id: AccountId, block: BlockNumber) -> i32 {
+            let checkpoints = self.checkpoints.get(voter_id).unwrap_or_default();
+            match checkpoints.partition_point(|(at, _)| *at <= block) {
+                0 => 0,
+                index => checkpoints[index - 1].1,
+            }
+        }
+
+        /// Reputation `who` received through `vote_categorized` under `category`.
+        #[ink(message)]
+        pub fn reputation_in_category(&self, who: AccountId, category: u8) -> i32 {
+            self.category_votes.get((who, category)).unwrap_or(0)
+        }
+
+        /// The caller's latest vote on `target` as `(value, power applied, timestamp)`.
+        #[ink(message)]
+        pub fn my_vote(&self, target: AccountId) -> Option<(TypeVote, i32, u64)> {
+            self.receipts
+                .get((self.env().caller(), target))
+                .map(|receipt| (receipt.value, receipt.power, receipt.at))
+        }
+
+        /// Timestamp of the most recent vote by anyone; 0 before the first vote.
+        #[ink(message)]
+        pub fn last_activity(&self) -> u64 {
+            self.last_global_vote
+        }
+
+        /// Timestamp of `who`'s most recent vote, or 0 if they never voted.
+        #[ink(message)]
+        pub fn last_vote_at(&self, who: AccountId) -> u64 {
+            self.last_vote_at.get(who).unwrap_or(0)
+        }
+
+        /// Recomputes `total_reputation`, the enabled voters' share of it and `voter_count` from
+        /// the member list and reports whether they still match the maintained counters.
+        /// Walks every member.
+        #[ink(message)]
+        pub fn verify_invariants(&self) -> bool {
+            let mut reputation: i64 = 0;
+            let mut enabled_reputation: i64 = 0;
+            let mut voters: u32 = 0;
+            for index in 0..self.member_count {
+                if let Some(who) = self.members.get(index) {
+                    let votes = i64::from(self.votes.get(who).unwrap_or(0));
+                    reputation += votes;
+                    if self.enabled_voters.contains(who) {
+                        enabled_reputation += votes;
+                        voters += 1;
+                    }
+                }
+            }
+            reputation == i64::from(self.total_reputation)
+                && enabled_reputation == self.enabled_reputation
+                && voters == self.voter_count
+        }
+
+        /// `who`'s reputation as basis points of the reputation held by enabled voters.
+        /// Zero for removed voters and when either figure is not positive.
+        #[ink(message)]
+        pub fn power_share(&self, who: AccountId) -> u32 {
+            if !self.enabled_voters.contains(who) {
+                return 0;
+            }
+            let reputation = i64::from(self.votes.get(who).unwrap_or(0));
+            let total = self.enabled_reputation;
+            if reputation <= 0 || total <= 0 {
+                return 0;
+            }
+            (reputation * 10_000 / total).min(i64::from(u32::MAX)) as u32
+        }
+
+        /// Mean reputation of enabled voters, or 0 with no voters. Removed voters' leftover
+        /// reputation is not counted. The division truncates toward zero, so a negative
+        /// average rounds up and a positive one rounds down.
+        #[ink(message)]
+        pub fn average_reputation(&self) -> i32 {
+            if self.voter_count == 0 {
+                return 0;
+            }
+            (self.enabled_reputation / i64::from(self.voter_count)) as i32
+        }
+
+        /// Raw `(likes, unlikes)` received by `voter_id`, ignoring vote power.
+        #[ink(message)]
+        pub fn vote_breakdown(&self, voter_id: AccountId) -> (u32, u32) {
+            (
+                self.likes_received.get(voter_id).unwrap_or(0),
+                self.unlikes_received.get(voter_id).unwrap_or(0),
+            )
+        }
+
+        /// Likes minus unlikes received by `target`, counting every vote as one whatever its power.
+        #[ink(message)]
+        pub fn sentiment(&self, target: AccountId) -> i32 {
+            let (likes, unlikes) = self.vote_breakdown(target);
+            (i64::from(likes) - i64::from(unlikes)).clamp(i64::from(i32::MIN), i64::from(i32::MAX)) as i32
+        }
+
+        /// Number of different accounts that have voted for `target`, however often each did.
+        #[ink(message)]
+        pub fn distinct_voters_of(&self, target: AccountId) -> u32 {
+            self.distinct_voters.get(target).unwrap_or(0)
+        }
+
+        /// Badges minted while casting a vote. Unlike `Stats::nfts_minted` this excludes
+        /// `claim_pending_mints`; compare with the collection's `total_minted` to spot
+        /// badges minted outside this contract.
+        #[ink(message)]
+        pub fn vote_mints(&self) -> u32 {
+            self.vote_mints
+        }
+
+        /// Code hash the current badge collection was instantiated from.
+        #[ink(message)]
+        pub fn nft_code_hash(&self) -> Hash {
+            self.nft_code_hash
+        }
+
+        /// Badges held by this contract itself; zero unless one was minted to it.
+        #[ink(message)]
+        pub fn self_nft_balance(&self) -> u32 {
+            self.contract.balance(self.env().account_id())
+        }
+
+        /// Id of the most recent badge minted to `who` through voting.
+        #[ink(message)]
+        pub fn last_minted_id(&self, who: AccountId) -> Option<Id> {
+            self.last_minted.get(who)
+        }
+
+        /// Ids of the badges held by `who`, capped at `MAX_PAGE`.
+        #[ink(message)]
+        pub fn my_tokens(&self, who: AccountId) -> Vec<Id> {
+            let count = self.contract.balance(who).min(MAX_PAGE);
+            (0..count)
+                .filter_map(|index| self.contract.token_by_index(who, index as u128))
+                .collect()
+        }
+
+        fn apply_vote(
+            &mut self,
+            voter_id: AccountId,
+            value: TypeVote,
+            mint_to: Option<AccountId>,
+            category: Option<u8>,
+            reason: Option<Hash>,
+        ) -> Result<(), Error> {
+            let caller = self.env().caller();
+            self.ensure_vote_allowed(caller, voter_id, &value, reason.is_some())?;
+            if let Some(category) = category {
+                let cast = self.category_votes_cast.get((caller, category)).unwrap_or(0);
+                if let Some(cap) = self.category_caps.get(category) {
+                    if cast >= cap {
+                        return Err(Error::CategoryCapReached);
+                    }
+                }
+                self.category_votes_cast.insert((caller, category), &(cast + 1));
+            }
+            let votes_received = self.votes_received.get(voter_id).unwrap_or(0);
+
+            let mut delta = self.effective_power(caller, value.clone());
+            if value == TypeVote::Unlike {
+                delta = self.damp_downvote(caller, voter_id, delta);
+            }
+            // A frozen target's vote is still counted, minted and emitted, with no effect.
+            if self.frozen.contains(voter_id) {
+                delta = 0;
+            }
+            let power = delta.abs();
+
+            let voter_votes = self.votes.get(voter_id).unwrap_or(0);
+            let old_band = self.power_of_vote(voter_votes);
+
+            let new_votes = voter_votes.checked_add(delta).ok_or(Error::Overflow)?;
+            self.set_reputation(voter_id, new_votes);
+            self.total_reputation = self.total_reputation.checked_add(delta).ok_or(Error::Overflow)?;
+            if let Some(category) = category {
+                let category_votes = self.category_votes.get((voter_id, category)).unwrap_or(0);
+                self.category_votes.insert((voter_id, category), &(category_votes + delta));
+            }
+            self.votes_received.insert(voter_id, &(votes_received + 1));
+            match value {
+                TypeVote::Like => {
+                    let likes = self.likes_received.get(voter_id).unwrap_or(0);
+                    self.likes_received.insert(voter_id, &(likes + 1));
+                }
+                TypeVote::Unlike => {
+                    let unlikes = self.unlikes_received.get(voter_id).unwrap_or(0);
+                    self.unlikes_received.insert(voter_id, &(unlikes + 1));
+                }
+            }
+            
+            let votes_cast = self.votes_cast.get(caller).unwrap_or(0) + 1;
+            self.votes_cast.insert(caller, &votes_cast);
+
+            if self.config.mint_enabled && votes_cast.is_multiple_of(self.config.votes_per_mint.max(1)) {
+                let default_recipient = if self.config.mint_to_target { voter_id } else { caller };
+                let recipient = mint_to.unwrap_or(default_recipient);
+                if self.config.recipient_must_be_voter && !self.enabled_voters.contains(recipient) {
+                    return Err(Error::RecipientNotVoter);
+                }
+                let id = self.contract.mint_token(recipient).map_err(|_| Error::NftNotMint)?;
+                self.last_minted.insert(recipient, &id);
+                self.nfts_minted += 1;
+                self.vote_mints += 1;
+                self.first_mint_done = true;
+                let badges = self.badges_minted.get(caller).unwrap_or(0);
+                self.badges_minted.insert(caller, &(badges + 1));
+            }
+            let now = self.env().block_timestamp();
+            self.last_active.insert(caller, &now);
+            self.last_vote_at.insert(caller, &now);
+            self.last_global_vote = now;
+            if !self.receipts.contains((caller, voter_id)) {
+                let distinct = self.distinct_voters.get(voter_id).unwrap_or(0);
+                self.distinct_voters.insert(voter_id, &(distinct + 1));
+            }
+            self.lock_stake(caller);
+            self.receipts.insert((caller, voter_id), &VoteReceipt { value: value.clone(), power: delta, at: now, category });
+
+            self.total_votes = self.total_votes.checked_add(power.max(1)).ok_or(Error::Overflow)?;
+
+            self.emit_vote(voter_id, value);
+            self.track_block_volume();
+            if let Some(reason) = reason {
+                let event_seq = self.next_event_seq();
+                self.env().emit_event(VoteReason { voter_id, reason, event_seq });
+            }
+
+            // Bands are relative to `total_votes`, so compare once it has been updated.
+            let new_band = self.power_of_vote(new_votes);
+            if new_band != old_band {
+                self.emit_band_changed(voter_id, old_band, new_band);
+            }
+
+            self.enforce_reputation_floor(voter_id, delta, new_votes);
+            Ok(())
+        }
+
+        /// Removes `voter_id` when a negative `delta` left them below `auto_remove_below`.
+        fn enforce_reputation_floor(&mut self, voter_id: AccountId, delta: i32, new_votes: i32) {
+            if let Some(floor) = self.config.auto_remove_below {
+                if delta < 0 && new_votes < floor && self.enabled_voters.contains(voter_id) {
+                    self.disable_voter(voter_id);
+                }
+            }
+        }
+
+        /// Applies an admin correction to `total_votes`, which must stay positive so
+        /// `power_of_vote` keeps a meaningful divisor.
+        fn adjust_total_votes(&mut self, delta: i32) -> Result<(), Error> {
+            let total_votes = self.total_votes.checked_add(delta).ok_or(Error::Overflow)?;
+            if total_votes <= 0 {
+                return Err(Error::InvalidAdjustment);
+            }
+            self.total_votes = total_votes;
+            Ok(())
+        }
+
+        /// Every check `apply_vote` makes before touching storage.
+        fn ensure_vote_allowed(
+            &self,
+            caller: AccountId,
+            voter_id: AccountId,
+            value: &TypeVote,
+            has_reason: bool,
+        ) -> Result<(), Error> {
+            self.ensure_can_vote(caller)?;
+            self.ensure_cooldown_elapsed(caller)?;
+            self.ensure_target_allowed(caller, voter_id, value, has_reason)?;
+            if let Some(max_votes) = self.config.max_votes_per_target {
+                if self.votes_received.get(voter_id).unwrap_or(0) >= max_votes {
+                    return Err(Error::TargetVoteCapReached);
+                }
+            }
+            Ok(())
+        }
+
+        /// Checks on the target and vote type, shared by new votes and `amend_vote`.
+        fn ensure_target_allowed(
+            &self,
+            caller: AccountId,
+            voter_id: AccountId,
+            value: &TypeVote,
+            has_reason: bool,
+        ) -> Result<(), Error> {
+            if self.config.downvotes_paused && *value == TypeVote::Unlike {
+                return Err(Error::DownvotesPaused);
+            }
+            if *value == TypeVote::Unlike && !self.can_downvote(caller) {
+                return Err(Error::CannotDownvote);
+            }
+            if self.config.require_downvote_reason && *value == TypeVote::Unlike && !has_reason {
+                return Err(Error::ReasonRequired);
+            }
+            if !self.enabled_voters.contains(voter_id) {
+                return Err(Error::VoterNotExist);
+            }
+            if !self.config.allow_self_vote && caller == voter_id {
+                return Err(Error::NotVoteItSelf);
+            }
+            if self.blocked_pairs.contains((caller, voter_id)) {
+                return Err(Error::PairBlocked);
+            }
+            if self.config.candidates_only && !self.candidates.contains(voter_id) {
+                return Err(Error::NotACandidate);
+            }
+            Ok(())
+        }
+
+        /// Checks that apply to `caller` regardless of target or vote type.
+        fn ensure_can_vote(&self, caller: AccountId) -> Result<(), Error> {
+            if self.paused {
+                return Err(Error::Paused);
+            }
+            if !self.is_voting_open() {
+                return Err(Error::VotingClosed);
+            }
+            if !self.enabled_voters.contains(caller) {
+                return Err(Error::NotIsVoter);
+            }
+            if !self.config.admin_can_vote && caller == self.admin.address {
+                return Err(Error::AdminCannotVote);
+            }
+            let joined = self.join_block.get(caller).unwrap_or(0);
+            if self.env().block_number() < joined.saturating_add(self.config.min_blocks_after_join) {
+                return Err(Error::AccountTooNew);
+            }
+            Ok(())
+        }
+
+        fn can_downvote(&self, caller: AccountId) -> bool {
+            !self.config.restrict_downvotes || self.band_of(self.power_basis(caller)) >= MIN_DOWNVOTE_BAND
+        }
+
+        /// Counts the vote against the current block and pauses voting past `auto_pause_threshold`.
+        fn track_block_volume(&mut self) {
+            let block = self.env().block_number();
+            let (last_block, count) = self.votes_in_block;
+            let votes = if last_block == block { count + 1 } else { 1 };
+            self.votes_in_block = (block, votes);
+            if let Some(threshold) = self.config.auto_pause_threshold {
+                if votes > threshold && !self.paused {
+                    self.paused = true;
+                    let event_seq = self.next_event_seq();
+                    self.env().emit_event(AutoPaused { block, votes, event_seq });
+                }
+            }
+        }
+
+        /// Kept apart from `ensure_can_vote` so `amend_vote` is not blocked by the vote it amends.
+        fn ensure_cooldown_elapsed(&self, caller: AccountId) -> Result<(), Error> {
+            let last = match self.last_vote_at.get(caller) {
+                Some(last) => last,
+                None => return Ok(()),
+            };
+            if self.env().block_timestamp() < last.saturating_add(self.cooldown_of(caller)) {
+                return Err(Error::CooldownActive);
+            }
+            Ok(())
+        }
+
+        /// Effective cooldown for `caller` in ms.
+        fn cooldown_of(&self, caller: AccountId) -> u64 {
+            let cooldown = self.config.cooldown_secs.saturating_mul(1000);
+            if !self.config.cooldown_scales_with_band {
+                return cooldown;
+            }
+            let band = self.power_of_vote(self.power_basis(caller)).max(1);
+            cooldown.saturating_mul(band as u64)
+        }
+
+        /// Scales an `Unlike` by the number of distinct downvoters `target` has had in the
+        /// current damping window, then records `caller` among them.
+        fn damp_downvote(&mut self, caller: AccountId, target: AccountId, delta: i32) -> i32 {
+            if self.config.downvote_damping_window.is_none() {
+                return delta;
+            }
+
+            let (start, mut count) = self.downvote_window(target);
+            let damped = delta / (1 << count.min(30));
+
+            if self.recent_downvoters.get((target, caller)) != Some(start) {
+                self.recent_downvoters.insert((target, caller), &start);
+                count += 1;
+            }
+            self.downvote_windows.insert(target, &(start, count));
+            damped
+        }
+
+        /// Start and distinct downvoter count of `target`'s current damping window.
+        /// Always `(now, 0)` when damping is disabled or the last window has lapsed.
+        fn downvote_window(&self, target: AccountId) -> (u64, u32) {
+            let now = self.env().block_timestamp();
+            match (self.config.downvote_damping_window, self.downvote_windows.get(target)) {
+                (Some(window), Some((start, count))) if now < start.saturating_add(window) => (start, count),
+                _ => (now, 0),
+            }
+        }
+
+        /// Applies an admin correction to one account. Callers update `total_votes`.
+        fn adjust_reputation(&mut self, voter_id: AccountId, delta: i32) -> Result<(), Error> {
+            let votes = self.votes.get(voter_id).unwrap_or(0);
+            let new_votes = votes.checked_add(delta).ok_or(Error::Overflow)?;
+            self.total_reputation = self.total_reputation.checked_add(delta).ok_or(Error::Overflow)?;
+
+            self.set_reputation(voter_id, new_votes);
+            self.track_member(voter_id);
+            self.emit_reputation_adjusted(voter_id, delta);
+            Ok(())
+        }
+
+        /// Sets `new` in `map` if `old` was set, and clears `old`.
+        fn move_flag<KeyType: StorageKey>(
+            map: &mut Mapping<AccountId, (), KeyType>,
+            old: AccountId,
+            new: AccountId,
+        ) {
+            if map.take(old).is_some() {
+                map.insert(new, &());
+            }
+        }
+
+        /// Adds `old`'s count in `map` to `new`'s and clears `old`'s.
+        fn move_count<KeyType: StorageKey>(
+            map: &mut Mapping<AccountId, u32, KeyType>,
+            old: AccountId,
+            new: AccountId,
+        ) -> Result<(), Error> {
+            if let Some(count) = map.take(old) {
+                let total = map.get(new).unwrap_or(0).checked_add(count).ok_or(Error::Overflow)?;
+                map.insert(new, &total);
+            }
+            Ok(())
+        }
+
+        fn track_member(&mut self, who: AccountId) {
+            if !self.member_index.contains(who) {
+                self.members.insert(self.member_count, &who);
+                self.member_index.insert(who, &self.member_count);
+                self.member_count += 1;
+            }
+        }
+
+        fn enable_voter(&mut self, voter_id: AccountId) -> Result<(), Error> {
+            let starting = self.config.starting_reputation;
+            if starting != 0 && !self.member_index.contains(voter_id) {
+                let votes = self.votes.get(voter_id).unwrap_or(0);
+                let new_votes = votes.checked_add(starting).ok_or(Error::Overflow)?;
+                self.total_votes = self.total_votes.checked_add(starting).ok_or(Error::Overflow)?;
+                self.total_reputation = self.total_reputation.checked_add(starting).ok_or(Error::Overflow)?;
+                self.set_reputation(voter_id, new_votes);
+            }
+            self.track_member(voter_id);
+            self.enabled_voters.insert(voter_id, &());
+            self.enabled_reputation += i64::from(self.votes.get(voter_id).unwrap_or(0));
+            self.join_block.insert(voter_id, &self.env().block_number());
+            self.pending_purge.remove(voter_id);
+            self.last_active.insert(voter_id, &self.env().block_timestamp());
+            self.voter_count += 1;
+            self.emit_new_voter(voter_id);
+            Ok(())
+        }
+
+        fn disable_voter(&mut self, voter_id: AccountId) {
+            self.enabled_voters.remove(voter_id);
+            self.enabled_reputation -= i64::from(self.votes.get(voter_id).unwrap_or(0));
+            if let Some(grace) = self.config.purge_grace_period {
+                let eligible_at = self.env().block_timestamp().saturating_add(grace);
+                self.pending_purge.insert(voter_id, &eligible_at);
+            }
+            self.voter_count -= 1;
+            self.emit_remove_voter(voter_id);
+        }
+
+        /// Single write path for `votes`, so every change is mirrored by `ReputationChanged`.
+        fn set_reputation(&mut self, voter_id: AccountId, after: i32) {
+            let before = self.votes.get(voter_id).unwrap_or(0);
+            self.votes.insert(voter_id, &after);
+            if self.enabled_voters.contains(voter_id) {
+                self.enabled_reputation += i64::from(after) - i64::from(before);
+            }
+            if before != after {
+                self.emit_reputation_changed(voter_id, before, after);
+            }
+        }
+
+        fn next_event_seq(&mut self) -> u64 {
+            self.event_seq += 1;
+            self.event_seq
+        }
+
+        fn emit_new_voter(&mut self, voter_id: AccountId) {
+            let by = self.env().caller();
+            let event_seq = self.next_event_seq();
+            if self.config.private_topics {
+                self.env().emit_event(PrivateNewVoter { voter_id, by, event_seq });
+            } else {
+                self.env().emit_event(NewVoter { voter_id, by, event_seq });
+            }
+        }
+
+        /// `by` is the caller, which for an `auto_remove_below` removal is the voter whose
+        /// vote triggered it rather than the admin.
+        fn emit_remove_voter(&mut self, voter_id: AccountId) {
+            let by = self.env().caller();
+            let event_seq = self.next_event_seq();
+            if self.config.private_topics {
+                self.env().emit_event(PrivateRemoveVoter { voter_id, by, event_seq });
+            } else {
+                self.env().emit_event(RemoveVoter { voter_id, by, event_seq });
+            }
+        }
+
+        fn emit_vote(&mut self, voter_id: AccountId, votation: TypeVote) {
+            let event_seq = self.next_event_seq();
+            let total_votes = self.total_votes;
+            if self.config.private_topics {
+                self.env().emit_event(PrivateVote { voter_id, total_votes, votation, event_seq });
+            } else {
+                self.env().emit_event(Vote { voter_id, total_votes, votation, event_seq });
+            }
+        }
+
+        fn emit_band_changed(&mut self, voter: AccountId, old_band: i32, new_band: i32) {
+            let event_seq = self.next_event_seq();
+            if self.config.private_topics {
+                self.env().emit_event(PrivateBandChanged { voter, old_band, new_band, event_seq });
+            } else {
+                self.env().emit_event(BandChanged { voter, old_band, new_band, event_seq });
+            }
+        }
+
+        fn emit_reputation_adjusted(&mut self, voter_id: AccountId, delta: i32) {
+            let event_seq = self.next_event_seq();
+            if self.config.private_topics {
+                self.env().emit_event(PrivateReputationAdjusted { voter_id, delta, event_seq });
+            } else {
+                self.env().emit_event(ReputationAdjusted { voter_id, delta, event_seq });
+            }
+        }
+
+        fn emit_flagged(&mut self, by: AccountId, who: AccountId) {
+            let event_seq = self.next_event_seq();
+            if self.config.private_topics {
+                self.env().emit_event(PrivateFlagged { by, who, event_seq });
+            } else {
+                self.env().emit_event(Flagged { by, who, event_seq });
+            }
+        }
+
+        fn emit_reputation_changed(&mut self, voter_id: AccountId, before: i32, after: i32) {
+            let event_seq = self.next_event_seq();
+            if self.config.private_topics {
+                self.env().emit_event(PrivateReputationChanged { voter_id, before, after, event_seq });
+            } else {
+                self.env().emit_event(ReputationChanged { voter_id, before, after, event_seq });
+            }
+        }
+
+        fn emit_voter_migrated(&mut self, old: AccountId, new: AccountId) {
+            let event_seq = self.next_event_seq();
+            if self.config.private_topics {
+                self.env().emit_event(PrivateVoterMigrated { old, new, event_seq });
+            } else {
+                self.env().emit_event(VoterMigrated { old, new, event_seq });
+            }
+        }
+
+        /// Reputation `power_of_vote` is computed from for `caller`.
+        fn power_basis(&self, caller: AccountId) -> i32 {
+            let local = self.votes.get(caller).unwrap_or(0);
+            match self.config.oracle {
+                Some(oracle) => {
+                    let oracle: ink::contract_ref!(ReputationOracle) = oracle.into();
+                    match oracle.call().reputation_of(caller).try_invoke() {
+                        Ok(Ok(reputation)) => reputation,
+                        _ => local,
+                    }
+                }
+                None => local,
+            }
+        }
+
+        /// `PSP34::balance_of(who)` on `collection`, or 0 if the call fails.
+        fn holdings_of(&self, collection: AccountId, who: AccountId) -> u32 {
+            build_call::<DefaultEnvironment>()
+                .call(collection)
+                .exec_input(
+                    ExecutionInput::new(Selector::new(ink::selector_bytes!("PSP34::balance_of")))
+                        .push_arg(who),
+                )
+                .returns::<u32>()
+                .try_invoke()
+                .ok()
+                .and_then(|result| result.ok())
+                .unwrap_or(0)
+        }
+
+        /// Restarts `caller`'s stake lock if they hold stake, which just lent power to a vote.
+        fn lock_stake(&mut self, caller: AccountId) {
+            if self.stakes.get(caller).unwrap_or(0) > 0 {
+                self.stake_locked_at.insert(caller, &self.env().block_timestamp());
+            }
+        }
+
+        fn stake_power(&self, caller: AccountId) -> i32 {
+            match self.stakes.get(caller).unwrap_or(0).checked_div(self.config.stake_unit) {
+                Some(units) => units.min(MAX_STAKE_POWER as Balance) as i32,
+                None => 0,
+            }
+        }
+
+        fn power_of_vote(&self, votes: i32) -> i32 {
+            i32::from(self.band_of(votes))
+        }
+    }
+
+    impl Votingtraits for Voting {
+    
+        #[ink(message)]
+        fn vote(&mut self, voter_id: AccountId, value: TypeVote) -> Result<(), Error> {        
+            self.vote(voter_id, value)
+        }
+
+        #[ink(message)]
+        fn add_voter(&mut self, voter_id: AccountId) -> Result<(), Error> {
+            self.add_voter(voter_id)
+        }
+
+        #[ink(message)]
+        fn remove_voter(&mut self, voter_id: AccountId) -> Result<(), Error> {
+            self.remove_voter(voter_id)
+        }
+
+        #[ink(message)]
+        fn get_reputation(&self, voter_id: AccountId) -> Result<i32, Error> {
+            Ok(self.get_reputation(voter_id).unwrap_or(0))
+        }
+
+        #[ink(message)]
+        fn get_balance(&self, voter_id: AccountId) -> Result<u32, Error> {
+            self.get_balance(voter_id)
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+        use ink::env::call::FromAccountId;
+        use ink::env::test;
+
+        type Accounts = test::DefaultAccounts<DefaultEnvironment>;
+        type Event = <Voting as ink::reflect::ContractEventBase>::Type;
+
+        const CODE_HASH: [u8; 32] = [0x01; 32];
+
+        /// Minting needs a deployed badge collection, which the off-chain environment lacks.
+        fn offline_config() -> Config {
+            Config {
+                mint_enabled: false,
+                ..Config::default()
+            }
+        }
+
+        /// Storage for a new instance at the current callee, without a badge collection behind it.
+        fn instantiate(admin: AccountId, config: Config) -> Voting {
+            let contract = ContractRef::from_account_id(AccountId::from([0xff; 32]));
+            Voting::with_contract(admin, contract, Hash::from(CODE_HASH), config)
+        }
+
+        /// Alice is admin; Bob, Charlie and Django are voters.
+        fn setup_with(config: Config) -> (Voting, Accounts) {
+            let accounts = test::default_accounts::<DefaultEnvironment>();
+            set_caller(accounts.alice);
+            let mut voting = instantiate(accounts.alice, config);
+            voting
+                .add_voters(vec![accounts.bob, accounts.charlie, accounts.django])
+                .unwrap();
+            (voting, accounts)
+        }
+
+        fn setup() -> (Voting, Accounts) {
+            setup_with(offline_config())
+        }
+
+        fn set_caller(who: AccountId) {
+            test::set_caller::<DefaultEnvironment>(who);
+        }
+
+        fn events() -> Vec<Event> {
+            test::recorded_events()
+                .map(|event| <Event as scale::Decode>::decode(&mut &event.data[..]).unwrap())
+                .collect()
+        }
+
+        fn reputation(voting: &Voting, who: AccountId) -> i32 {
+            voting.votes.get(who).unwrap_or(0)
+        }
+
+        #[ink::test]
+        fn commit_requires_voter() {
+            let (mut voting, accounts) = setup_with(Config {
+                commit_reveal: true,
+                ..offline_config()
+            });
+            set_caller(accounts.eve);
+            assert_eq!(voting.commit_vote([0; 32]), Err(Error::NotIsVoter));
+        }
+
+        #[ink::test]
+        fn commit_reveal_disabled_by_default() {
+            let (mut voting, accounts) = setup();
+            set_caller(accounts.bob);
+            assert_eq!(voting.commit_vote([0; 32]), Err(Error::CommitRevealDisabled));
+            assert_eq!(
+                voting.reveal_vote(accounts.charlie, TypeVote::Like, [0; 32]),
+                Err(Error::CommitRevealDisabled)
+            );
+        }
+
+        #[ink::test]
+        fn admin_adjust_reputation_works() {
+            let (mut voting, accounts) = setup();
+            voting.admin_adjust_reputation(accounts.bob, 10).unwrap();
+            voting.admin_adjust_reputation(accounts.bob, -4).unwrap();
+            assert_eq!(reputation(&voting, accounts.bob), 6);
+            assert_eq!(voting.stats().total_votes, 6);
+            assert_eq!(voting.stats().total_reputation, 6);
+        }
+
+        #[ink::test]
+        fn admin_adjust_reputation_rejects_overflow() {
+            let (mut voting, accounts) = setup();
+            voting.admin_adjust_reputation(accounts.bob, 10).unwrap();
+            assert_eq!(voting.admin_adjust_reputation(accounts.bob, i32::MAX), Err(Error::Overflow));
+            assert_eq!(reputation(&voting, accounts.bob), 10);
+        }
+
+        #[ink::test]
+        fn admin_adjust_reputation_keeps_total_votes_positive() {
+            let (mut voting, accounts) = setup();
+            voting.admin_adjust_reputation(accounts.bob, 10).unwrap();
+            assert_eq!(voting.admin_adjust_reputation(accounts.charlie, -10), Err(Error::InvalidAdjustment));
+        }
+
+        #[ink::test]
+        fn admin_adjust_reputation_requires_admin() {
+            let (mut voting, accounts) = setup();
+            set_caller(accounts.bob);
+            assert_eq!(voting.admin_adjust_reputation(accounts.bob, 10), Err(Error::NotIsAdmin));
+        }
+
+        #[ink::test]
+        fn set_max_votes_per_target_requires_admin() {
+            let (mut voting, accounts) = setup();
+            set_caller(accounts.bob);
+            assert_eq!(voting.set_max_votes_per_target(Some(1)), Err(Error::NotIsAdmin));
+        }
+
+        fn set_timestamp(timestamp: u64) {
+            test::set_block_timestamp::<DefaultEnvironment>(timestamp);
+        }
+
+        #[ink::test]
+        fn is_voting_open_follows_window() {
+            let (voting, _) = setup_with(Config {
+                voting_start: 1000,
+                voting_end: 2000,
+                ..offline_config()
+            });
+            set_timestamp(999);
+            assert!(!voting.is_voting_open());
+            set_timestamp(1000);
+            assert!(voting.is_voting_open());
+            set_timestamp(1999);
+            assert!(voting.is_voting_open());
+            set_timestamp(2000);
+            assert!(!voting.is_voting_open());
+        }
+
+        #[ink::test]
+        fn votes_outside_window_are_rejected() {
+            let (mut voting, accounts) = setup_with(Config {
+                voting_start: 1000,
+                voting_end: 2000,
+                ..offline_config()
+            });
+            set_caller(accounts.bob);
+            set_timestamp(999);
+            assert_eq!(voting.vote(accounts.charlie, TypeVote::Like), Err(Error::VotingClosed));
+            // Inside the window the vote gets as far as the target check.
+            set_timestamp(1000);
+            assert_eq!(voting.vote(accounts.eve, TypeVote::Like), Err(Error::VoterNotExist));
+            set_timestamp(2000);
+            assert_eq!(voting.vote(accounts.charlie, TypeVote::Like), Err(Error::VotingClosed));
+        }
+
+        #[ink::test]
+        fn extend_voting_only_moves_end_later() {
+            let (mut voting, accounts) = setup_with(Config {
+                voting_end: 2000,
+                ..offline_config()
+            });
+            assert_eq!(voting.extend_voting(1500), Err(Error::InvalidWindow));
+            assert_eq!(voting.extend_voting(3000), Ok(()));
+
+            set_caller(accounts.bob);
+            set_timestamp(2500);
+            assert!(voting.is_voting_open());
+            assert_eq!(voting.extend_voting(4000), Err(Error::NotIsAdmin));
+        }
+
+        #[ink::test]
+        fn effective_power_follows_band() {
+            let (mut voting, accounts) = setup();
+            voting.admin_adjust_reputation(accounts.bob, 50).unwrap();
+            assert_eq!(voting.effective_power(accounts.bob, TypeVote::Like), 3);
+            assert_eq!(voting.effective_power(accounts.bob, TypeVote::Unlike), -3);
+            assert_eq!(voting.effective_power(accounts.charlie, TypeVote::Like), 1);
+        }
+
+        #[ink::test]
+        fn remove_inactive_removes_idle_voters() {
+            let (mut voting, accounts) = setup();
+            set_timestamp(1000);
+            voting.add_voter(accounts.eve).unwrap();
+
+            assert_eq!(voting.remove_inactive(500, 0, 10), Ok(3));
+            assert_eq!(voting.stats().voter_count, 1);
+            assert!(voting.enabled_voters.contains(accounts.eve));
+            assert_eq!(voting.remove_inactive(500, 0, 10), Ok(0));
+        }
+
+        #[ink::test]
+        fn remove_inactive_requires_admin() {
+            let (mut voting, accounts) = setup();
+            set_caller(accounts.bob);
+            assert_eq!(voting.remove_inactive(500, 0, 10), Err(Error::NotIsAdmin));
+        }
+
+        #[ink::test]
+        fn remove_inactive_walks_one_page() {
+            let (mut voting, accounts) = setup();
+            set_timestamp(1000);
+            assert_eq!(voting.remove_inactive(500, 2, 10), Ok(1));
+            assert!(!voting.enabled_voters.contains(accounts.django));
+            assert_eq!(voting.remove_inactive(500, 1, 1), Ok(1));
+            assert!(!voting.enabled_voters.contains(accounts.charlie));
+            assert!(voting.enabled_voters.contains(accounts.bob));
+
+            for index in 0..60 {
+                voting.add_voter(AccountId::from([100 + index; 32])).unwrap();
+            }
+            set_timestamp(3000);
+            assert_eq!(voting.remove_inactive(2000, 3, u32::MAX), Ok(MAX_BATCH as u32));
+            assert_eq!(voting.stats().voter_count, 61 - MAX_BATCH as u32);
+        }
+
+        /// Topics of every recorded event `filter` accepts, in emission order.
+        fn topics_of(filter: fn(&Event) -> bool) -> Vec<Vec<Vec<u8>>> {
+            test::recorded_events()
+                .filter(|event| filter(&<Event as scale::Decode>::decode(&mut &event.data[..]).unwrap()))
+                .map(|event| event.topics)
+                .collect()
+        }
+
+        /// Topic ink! derives from `value` in the `field` of `event`.
+        fn topic<T: Encode>(event: &str, field: &str, value: &T) -> Vec<u8> {
+            let prefix = format!("Voting::{event}::{field}");
+            let encoded = ink::env::topics::PrefixedValue { prefix: prefix.as_bytes(), value }.encode();
+            let mut topic = [0; 32];
+            ink::env::hash_bytes::<ink::env::hash::Blake2x256>(&encoded, &mut topic);
+            topic.to_vec()
+        }
+
+        #[ink::test]
+        fn adjust_topics_include_voter_id() {
+            let (mut voting, accounts) = setup();
+            voting.admin_adjust_reputation(accounts.charlie, 1).unwrap();
+
+            let topics = topics_of(|event| matches!(event, Event::ReputationAdjusted(_)));
+            assert_eq!(topics.len(), 1);
+            assert!(topics[0].contains(&topic("ReputationAdjusted", "voter_id", &accounts.charlie)));
+        }
+
+        #[ink::test]
+        fn private_topics_hide_voter_id() {
+            let (mut voting, accounts) = setup_with(Config {
+                private_topics: true,
+                ..offline_config()
+            });
+            voting.admin_adjust_reputation(accounts.charlie, 1).unwrap();
+            voting.remove_voter(accounts.django).unwrap();
+
+            assert!(topics_of(|event| matches!(event, Event::ReputationAdjusted(_) | Event::NewVoter(_))).is_empty());
+            // Only the event signature is left as a topic.
+            let topics = topics_of(|event| matches!(event, Event::PrivateReputationAdjusted(_)));
+            assert_eq!(topics.len(), 1);
+            assert_eq!(topics[0].len(), 1);
+            assert_eq!(topics_of(|event| matches!(event, Event::PrivateNewVoter(_))).len(), 3);
+            let topics = topics_of(|event| matches!(event, Event::PrivateRemoveVoter(_)));
+            assert_eq!(topics.len(), 1);
+            assert_eq!(topics[0].len(), 1);
+        }
+
+        #[ink::test]
+        fn moderator_flag_requires_threshold() {
+            let (mut voting, accounts) = setup_with(Config {
+                moderator_threshold: 5,
+                ..offline_config()
+            });
+            set_caller(accounts.bob);
+            assert_eq!(voting.moderator_flag(accounts.eve), Err(Error::InsufficientReputation));
+
+            set_caller(accounts.alice);
+            voting.admin_adjust_reputation(accounts.bob, 5).unwrap();
+            set_caller(accounts.bob);
+            assert_eq!(voting.moderator_flag(accounts.eve), Ok(()));
+            assert!(voting.is_flagged(accounts.eve));
+            assert!(!voting.is_flagged(accounts.frank));
+
+            let topics = topics_of(|event| matches!(event, Event::Flagged(_)));
+            assert_eq!(topics.len(), 1);
+            assert!(topics[0].contains(&topic("Flagged", "by", &accounts.bob)));
+            assert!(topics[0].contains(&topic("Flagged", "who", &accounts.eve)));
+        }
+
+        #[ink::test]
+        fn moderator_flag_requires_voter() {
+            let (mut voting, accounts) = setup_with(Config {
+                moderator_threshold: 0,
+                ..offline_config()
+            });
+            set_caller(accounts.eve);
+            assert_eq!(voting.moderator_flag(accounts.bob), Err(Error::NotIsVoter));
+        }
+
+        #[ink::test]
+        fn moderator_flag_private_topics() {
+            let (mut voting, accounts) = setup_with(Config {
+                moderator_threshold: 0,
+                private_topics: true,
+                ..offline_config()
+            });
+            set_caller(accounts.bob);
+            voting.moderator_flag(accounts.eve).unwrap();
+
+            assert!(topics_of(|event| matches!(event, Event::Flagged(_))).is_empty());
+            let topics = topics_of(|event| matches!(event, Event::PrivateFlagged(_)));
+            assert_eq!(topics.len(), 1);
+            assert_eq!(topics[0].len(), 1);
+        }
+
+        #[ink::test]
+        fn downvote_damping_halves_each_further_downvoter() {
+            let (mut voting, accounts) = setup_with(Config {
+                downvote_damping_window: Some(1000),
+                ..offline_config()
+            });
+            assert_eq!(voting.damp_downvote(accounts.bob, accounts.eve, -8), -8);
+            assert_eq!(voting.damp_downvote(accounts.charlie, accounts.eve, -8), -4);
+            assert_eq!(voting.damp_downvote(accounts.django, accounts.eve, -8), -2);
+
+            set_timestamp(1000);
+            assert_eq!(voting.damp_downvote(accounts.frank, accounts.eve, -8), -8);
+        }
+
+        #[ink::test]
+        fn downvote_damping_ignores_repeat_downvoter() {
+            let (mut voting, accounts) = setup_with(Config {
+                downvote_damping_window: Some(1000),
+                ..offline_config()
+            });
+            assert_eq!(voting.damp_downvote(accounts.bob, accounts.charlie, -8), -8);
+            assert_eq!(voting.damp_downvote(accounts.bob, accounts.charlie, -8), -4);
+            assert_eq!(voting.damp_downvote(accounts.bob, accounts.charlie, -8), -4);
+        }
+
+        #[ink::test]
+        fn export_import_round_trip() {
+            let (mut voting, accounts) = setup();
+            voting.admin_adjust_reputation(accounts.charlie, 1).unwrap();
+            voting.remove_voter(accounts.django).unwrap();
+
+            let exported = voting.export_state(0, 10).unwrap();
+            assert_eq!(
+                exported,
+                vec![(accounts.bob, 0, true), (accounts.charlie, 1, true), (accounts.django, 0, false)]
+            );
+            assert_eq!(voting.export_state(1, 1).unwrap(), vec![(accounts.charlie, 1, true)]);
+
+            test::set_callee::<DefaultEnvironment>(AccountId::from([0xee; 32]));
+            let mut restored = instantiate(accounts.alice, offline_config());
+            restored.import_state(exported.clone()).unwrap();
+            assert_eq!(restored.export_state(0, 10).unwrap(), exported);
+            assert_eq!(restored.stats().voter_count, 2);
+            assert_eq!(restored.stats().total_reputation, 1);
+            assert!(restored.verify_invariants());
+        }
+
+        #[ink::test]
+        fn export_import_require_admin() {
+            let (mut voting, accounts) = setup();
+            set_caller(accounts.bob);
+            assert_eq!(voting.export_state(0, 10), Err(Error::NotIsAdmin));
+            assert_eq!(voting.import_state(Vec::new()), Err(Error::NotIsAdmin));
+        }
+
+        #[ink::test]
+        fn import_state_rejects_oversized_batches() {
+            let (mut voting, _) = setup();
+            let entries = (0..=MAX_BATCH as u8)
+                .map(|index| (AccountId::from([100 + index; 32]), 0, true))
+                .collect();
+            assert_eq!(voting.import_state(entries), Err(Error::BatchTooLarge));
+        }
+
+        #[ink::test]
+        fn purge_after_grace_period() {
+            let (mut voting, accounts) = setup_with(Config {
+                purge_grace_period: Some(1000),
+                ..offline_config()
+            });
+            voting.admin_adjust_reputation(accounts.charlie, 1).unwrap();
+            voting.remove_voter(accounts.charlie).unwrap();
+
+            set_timestamp(999);
+            assert_eq!(voting.purge(accounts.charlie), Err(Error::PurgeNotReady));
+            set_timestamp(1000);
+            assert_eq!(voting.purge(accounts.charlie), Ok(()));
+            assert_eq!(reputation(&voting, accounts.charlie), 0);
+            assert_eq!(voting.stats().total_reputation, 0);
+            assert_eq!(voting.purge(accounts.charlie), Err(Error::NoPendingPurge));
+        }
+
+        #[ink::test]
+        fn purge_needs_removed_voter() {
+            let (mut voting, accounts) = setup_with(Config {
+                purge_grace_period: Some(1000),
+                ..offline_config()
+            });
+            assert_eq!(voting.purge(accounts.bob), Err(Error::NoPendingPurge));
+
+            voting.remove_voter(accounts.bob).unwrap();
+            voting.add_voter(accounts.bob).unwrap();
+            set_timestamp(1000);
+            assert_eq!(voting.purge(accounts.bob), Err(Error::NoPendingPurge));
+        }
+
+        #[ink::test]
+        fn vote_and_tip_rejects_value_mismatch() {
+            let (mut voting, accounts) = setup();
+            set_caller(accounts.bob);
+            test::set_value_transferred::<DefaultEnvironment>(50);
+            assert_eq!(voting.vote_and_tip(accounts.charlie, TypeVote::Like, 100), Err(Error::ValueMismatch));
+            assert_eq!(reputation(&voting, accounts.charlie), 0);
+        }
+
+        #[ink::test]
+        fn votes_go_through_with_minting_disabled() {
+            let (mut voting, accounts) = setup();
+            set_caller(accounts.bob);
+            assert_eq!(voting.vote(accounts.charlie, TypeVote::Like), Ok(()));
+            assert_eq!(voting.stats().nfts_minted, 0);
+            assert_eq!(voting.votes_cast.get(accounts.bob), Some(1));
+            assert_eq!(voting.claim_pending_mints(), Err(Error::MintDisabled));
+        }
+
+        #[ink::test]
+        fn candidates_only_restricts_targets() {
+            let (mut voting, accounts) = setup_with(Config {
+                candidates_only: true,
+                ..offline_config()
+            });
+            voting.add_candidate(accounts.charlie).unwrap();
+
+            set_caller(accounts.bob);
+            assert_eq!(voting.vote(accounts.django, TypeVote::Like), Err(Error::NotACandidate));
+            assert_eq!(voting.vote(accounts.charlie, TypeVote::Like), Ok(()));
+
+            set_caller(accounts.alice);
+            voting.remove_candidate(accounts.charlie).unwrap();
+            assert_eq!(voting.remove_candidate(accounts.charlie), Err(Error::NotACandidate));
+            set_caller(accounts.bob);
+            assert_eq!(voting.vote(accounts.charlie, TypeVote::Like), Err(Error::NotACandidate));
+        }
+
+        #[ink::test]
+        fn candidates_require_admin() {
+            let (mut voting, accounts) = setup();
+            set_caller(accounts.bob);
+            assert_eq!(voting.add_candidate(accounts.charlie), Err(Error::NotIsAdmin));
+            assert_eq!(voting.remove_candidate(accounts.charlie), Err(Error::NotIsAdmin));
+        }
+
+        #[ink::test]
+        fn vote_breakdown_counts_raw_votes() {
+            let (mut voting, accounts) = setup();
+            voting.admin_adjust_reputation(accounts.bob, 100).unwrap();
+            assert_eq!(voting.vote_breakdown(accounts.charlie), (0, 0));
+
+            set_caller(accounts.bob);
+            voting.vote(accounts.charlie, TypeVote::Like).unwrap();
+            voting.vote(accounts.charlie, TypeVote::Like).unwrap();
+            set_caller(accounts.django);
+            voting.vote(accounts.charlie, TypeVote::Unlike).unwrap();
+            assert_eq!(voting.vote_breakdown(accounts.charlie), (2, 1));
+        }
+
+        #[ink::test]
+        fn round_mode_applies_to_bands() {
+            let (mut voting, accounts) = setup();
+            voting.admin_adjust_reputation(accounts.bob, 300).unwrap();
+
+            for (mode, at_100, at_101) in [
+                (RoundMode::Floor, 1, 1),
+                (RoundMode::Ceil, 2, 2),
+                (RoundMode::Nearest, 1, 2),
+            ] {
+                voting.config.round_mode = mode;
+                assert_eq!(voting.power_of_vote(100), at_100, "{mode:?}");
+                assert_eq!(voting.power_of_vote(101), at_101, "{mode:?}");
+                assert_eq!(voting.power_of_vote(99), 1, "{mode:?}");
+            }
+        }
+
+        #[ink::test]
+        fn event_seq_numbers_every_event() {
+            let (mut voting, accounts) = setup();
+            set_caller(accounts.bob);
+            voting.vote(accounts.charlie, TypeVote::Like).unwrap();
+            set_caller(accounts.alice);
+            voting.admin_adjust_reputation(accounts.django, 5).unwrap();
+            voting.remove_voter(accounts.django).unwrap();
+
+            // `event_seq` is the last field of every event.
+            let seqs: Vec<u64> = test::recorded_events()
+                .map(|event| u64::decode(&mut &event.data[event.data.len() - 8..]).unwrap())
+                .collect();
+            assert_eq!(seqs, (1..=voting.event_seq).collect::<Vec<_>>());
+        }
+
+        #[ink::test]
+        fn rank_of_orders_enabled_voters() {
+            let (mut voting, accounts) = setup();
+            voting.admin_adjust_reputation(accounts.bob, 10).unwrap();
+            voting.admin_adjust_reputation(accounts.charlie, 10).unwrap();
+            voting.admin_adjust_reputation(accounts.django, 3).unwrap();
+
+            assert_eq!(voting.rank_of(accounts.bob), Some(1));
+            assert_eq!(voting.rank_of(accounts.charlie), Some(1));
+            assert_eq!(voting.rank_of(accounts.django), Some(3));
+            assert_eq!(voting.rank_of(accounts.eve), None);
+
+            voting.remove_voter(accounts.charlie).unwrap();
+            assert_eq!(voting.rank_of(accounts.django), Some(2));
+            assert_eq!(voting.rank_of(accounts.charlie), None);
+        }
+
+        #[ink::test]
+        fn downvotes_can_be_paused() {
+            let (mut voting, accounts) = setup();
+            voting.set_downvotes_paused(true).unwrap();
+
+            set_caller(accounts.bob);
+            assert_eq!(voting.vote(accounts.charlie, TypeVote::Unlike), Err(Error::DownvotesPaused));
+            assert_eq!(voting.vote(accounts.charlie, TypeVote::Like), Ok(()));
+            assert_eq!(voting.set_downvotes_paused(false), Err(Error::NotIsAdmin));
+
+            set_caller(accounts.alice);
+            voting.set_downvotes_paused(false).unwrap();
+            set_caller(accounts.bob);
+            assert_eq!(voting.vote(accounts.charlie, TypeVote::Unlike), Ok(()));
+        }
+
+        #[ink::test]
+        fn starting_reputation_granted_once() {
+            let (mut voting, accounts) = setup_with(Config {
+                starting_reputation: 10,
+                ..offline_config()
+            });
+            assert_eq!(reputation(&voting, accounts.bob), 10);
+            assert_eq!(voting.stats().total_votes, 30);
+            assert_eq!(voting.stats().total_reputation, 30);
+
+            voting.remove_voter(accounts.bob).unwrap();
+            voting.add_voter(accounts.bob).unwrap();
+            assert_eq!(reputation(&voting, accounts.bob), 10);
+            assert_eq!(voting.stats().total_reputation, 30);
+            assert!(voting.verify_invariants());
+        }
+
+        #[ink::test]
+        fn starting_reputation_must_not_be_negative() {
+            let accounts = test::default_accounts::<DefaultEnvironment>();
+            let config = Config {
+                starting_reputation: -1,
+                ..offline_config()
+            };
+            assert!(matches!(
+                Voting::new_with_config(accounts.alice, Hash::from(CODE_HASH), config),
+                Err(Error::InvalidStartingReputation)
+            ));
+        }
+
+        #[ink::test]
+        fn starting_reputation_checks_overflow() {
+            let accounts = test::default_accounts::<DefaultEnvironment>();
+            let mut voting = instantiate(accounts.alice, Config {
+                starting_reputation: i32::MAX,
+                ..offline_config()
+            });
+            assert_eq!(voting.add_voter(accounts.bob), Ok(()));
+            assert_eq!(voting.add_voter(accounts.charlie), Err(Error::Overflow));
+        }
+
+        #[ink::test]
+        fn can_i_vote_reports_blocking_error() {
+            let (mut voting, accounts) = setup_with(Config {
+                cooldown_secs: 10,
+                ..offline_config()
+            });
+            set_caller(accounts.eve);
+            assert_eq!(voting.can_i_vote(), Err(Error::NotIsVoter));
+
+            set_caller(accounts.bob);
+            assert_eq!(voting.can_i_vote(), Ok(()));
+            voting.vote(accounts.charlie, TypeVote::Like).unwrap();
+            assert_eq!(voting.can_i_vote(), Err(Error::CooldownActive));
+
+            set_timestamp(10_000);
+            assert_eq!(voting.can_i_vote(), Ok(()));
+            voting.paused = true;
+            assert_eq!(voting.can_i_vote(), Err(Error::Paused));
+            voting.paused = false;
+            voting.config.voting_end = 10_000;
+            assert_eq!(voting.can_i_vote(), Err(Error::VotingClosed));
+        }
+
+        #[ink::test]
+        fn recipient_must_be_voter() {
+            let (mut voting, accounts) = setup_with(Config {
+                mint_enabled: true,
+                recipient_must_be_voter: true,
+                ..Config::default()
+            });
+            set_caller(accounts.bob);
+            assert_eq!(
+                voting.vote_with_recipient(accounts.charlie, TypeVote::Like, Some(accounts.eve)),
+                Err(Error::RecipientNotVoter)
+            );
+        }
+
+        #[ink::test]
+        fn band_of_boundaries() {
+            let (mut voting, accounts) = setup();
+            assert_eq!(voting.band_of(0), 1);
+
+            voting.admin_adjust_reputation(accounts.bob, 100).unwrap();
+            for (reputation, band) in [(-1, 0), (0, 1), (33, 1), (34, 2), (66, 2), (67, 3), (100, 3)] {
+                assert_eq!(voting.band_of(reputation), band, "{reputation}");
+            }
+        }
+
+        #[ink::test]
+        fn band_of_extreme_reputation() {
+            let (mut voting, _) = setup();
+            voting.total_votes = 1;
+            assert_eq!(voting.band_of(i32::MAX), 3);
+            assert_eq!(voting.band_of(i32::MIN), 0);
+            voting.total_votes = i32::MAX;
+            assert_eq!(voting.band_of(i32::MAX), 3);
+            assert_eq!(voting.band_of(1), 1);
+        }
+
+        fn stake(voting: &mut Voting, who: AccountId, amount: Balance) {
+            set_caller(who);
+            test::set_value_transferred::<DefaultEnvironment>(amount);
+            voting.stake().unwrap();
+            test::set_value_transferred::<DefaultEnvironment>(0);
+        }
+
+        #[ink::test]
+        fn stake_adds_vote_power() {
+            let (mut voting, accounts) = setup_with(Config {
+                stake_unit: 10,
+                ..offline_config()
+            });
+            stake(&mut voting, accounts.bob, 25);
+            assert_eq!(voting.stake_of(accounts.bob), 25);
+            assert_eq!(voting.effective_power(accounts.bob, TypeVote::Like), 3);
+
+            test::set_account_balance::<DefaultEnvironment>(test::callee::<DefaultEnvironment>(), 25);
+            set_timestamp(STAKE_LOCK_MS);
+            assert_eq!(voting.unstake(30), Err(Error::InsufficientStake));
+            assert_eq!(voting.unstake(10), Ok(()));
+            assert_eq!(voting.stake_of(accounts.bob), 15);
+            assert_eq!(voting.effective_power(accounts.bob, TypeVote::Like), 2);
+        }
+
+        #[ink::test]
+        fn stake_power_is_capped() {
+            let (mut voting, accounts) = setup_with(Config {
+                stake_unit: 1,
+                ..offline_config()
+            });
+            stake(&mut voting, accounts.bob, Balance::MAX);
+            assert_eq!(voting.effective_power(accounts.bob, TypeVote::Like), 1 + MAX_STAKE_POWER);
+            voting.vote(accounts.charlie, TypeVote::Like).unwrap();
+            assert_eq!(reputation(&voting, accounts.charlie), 1 + MAX_STAKE_POWER);
+        }
+
+        #[ink::test]
+        fn stake_rejects_overflow() {
+            let (mut voting, accounts) = setup();
+            stake(&mut voting, accounts.bob, Balance::MAX);
+            test::set_value_transferred::<DefaultEnvironment>(1);
+            assert_eq!(voting.stake(), Err(Error::Overflow));
+            assert_eq!(voting.stake_of(accounts.bob), Balance::MAX);
+        }
+
+        #[ink::test]
+        fn stake_without_unit_adds_nothing() {
+            let (mut voting, accounts) = setup();
+            stake(&mut voting, accounts.bob, 1000);
+            assert_eq!(voting.effective_power(accounts.bob, TypeVote::Like), 1);
+        }
+
+        #[ink::test]
+        fn stake_locked_after_stake_and_vote() {
+            let (mut voting, accounts) = setup_with(Config {
+                stake_unit: 10,
+                ..offline_config()
+            });
+            test::set_account_balance::<DefaultEnvironment>(test::callee::<DefaultEnvironment>(), 25);
+            stake(&mut voting, accounts.bob, 25);
+            assert_eq!(voting.unstake(10), Err(Error::StakeLocked));
+
+            set_timestamp(1000);
+            voting.vote(accounts.charlie, TypeVote::Like).unwrap();
+            set_timestamp(STAKE_LOCK_MS);
+            assert_eq!(voting.unstake(10), Err(Error::StakeLocked));
+            set_timestamp(STAKE_LOCK_MS + 1000);
+            assert_eq!(voting.unstake(10), Ok(()));
+            assert_eq!(voting.stake_of(accounts.bob), 15);
+        }
+
+        #[ink::test]
+        fn last_vote_at_records_caller() {
+            let (mut voting, accounts) = setup();
+            assert_eq!(voting.last_vote_at(accounts.bob), 0);
+
+            set_timestamp(5000);
+            set_caller(accounts.bob);
+            voting.vote(accounts.charlie, TypeVote::Like).unwrap();
+            assert_eq!(voting.last_vote_at(accounts.bob), 5000);
+            assert_eq!(voting.last_vote_at(accounts.charlie), 0);
+        }
+
+        fn accounts_batch(len: u8) -> Vec<AccountId> {
+            (0..len).map(|index| AccountId::from([100 + index; 32])).collect()
+        }
+
+        #[ink::test]
+        fn batches_are_capped() {
+            let (mut voting, _) = setup();
+            assert_eq!(voting.add_voters(accounts_batch(51)), Err(Error::BatchTooLarge));
+            assert_eq!(voting.remove_voters(accounts_batch(51)), Err(Error::BatchTooLarge));
+            assert_eq!(voting.stats().voter_count, 3);
+
+            assert_eq!(voting.add_voters(accounts_batch(50)), Ok(()));
+            assert_eq!(voting.remove_voters(accounts_batch(50)), Ok(()));
+            assert_eq!(voting.stats().voter_count, 3);
+        }
+
+        #[ink::test]
+        fn reputation_changed_reports_before_and_after() {
+            let (mut voting, accounts) = setup();
+            voting.admin_adjust_reputation(accounts.django, 10).unwrap();
+            set_caller(accounts.bob);
+            voting.vote(accounts.charlie, TypeVote::Like).unwrap();
+            set_caller(accounts.alice);
+            voting.admin_adjust_reputation(accounts.charlie, 1).unwrap();
+            voting.admin_adjust_reputation(accounts.charlie, -2).unwrap();
+            voting.admin_adjust_reputation(accounts.bob, 0).unwrap();
+
+            let changes: Vec<_> = events()
+                .into_iter()
+                .filter_map(|event| match event {
+                    Event::ReputationChanged(ReputationChanged { voter_id, before, after, .. }) => {
+                        Some((voter_id, before, after))
+                    }
+                    _ => None,
+                })
+                .collect();
+            assert_eq!(
+                changes,
+                vec![
+                    (accounts.django, 0, 10),
+                    (accounts.charlie, 0, 1),
+                    (accounts.charlie, 1, 2),
+                    (accounts.charlie, 2, 0),
+                ]
+            );
+        }
+
+        #[ink::test]
+        fn checkpoints_record_reputation_per_block() {
+            let (mut voting, accounts) = setup();
+            voting.admin_adjust_reputation(accounts.bob, 5).unwrap();
+            set_caller(accounts.bob);
+            voting.checkpoint(accounts.bob).unwrap();
+
+            test::advance_block::<DefaultEnvironment>();
+            set_caller(accounts.alice);
+            voting.admin_adjust_reputation(accounts.bob, 5).unwrap();
+            voting.checkpoint(accounts.bob).unwrap();
+            voting.admin_adjust_reputation(accounts.bob, 1).unwrap();
+            voting.checkpoint(accounts.bob).unwrap();
+
+            assert_eq!(voting.reputation_at_block(accounts.bob, 0), 5);
+            assert_eq!(voting.reputation_at_block(accounts.bob, 1), 11);
+            assert_eq!(voting.reputation_at_block(accounts.bob, 9), 11);
+            assert_eq!(voting.reputation_at_block(accounts.charlie, 1), 0);
+            assert_eq!(voting.checkpoints.get(accounts.bob).unwrap().len(), 2);
+        }
+
+        #[ink::test]
+        fn checkpoint_restricted_to_voter_and_admin() {
+            let (mut voting, accounts) = setup();
+            set_caller(accounts.charlie);
+            assert_eq!(voting.checkpoint(accounts.bob), Err(Error::MustBeItSelf));
+            assert!(voting.checkpoints.get(accounts.bob).is_none());
+        }
+
+        #[ink::test]
+        fn checkpoints_are_capped() {
+            let (mut voting, accounts) = setup();
+            let full: Vec<Checkpoint> = (0..MAX_CHECKPOINTS as BlockNumber).map(|block| (block, 0)).collect();
+            voting.checkpoints.insert(accounts.bob, &full);
+
+            test::advance_block::<DefaultEnvironment>();
+            assert_eq!(voting.checkpoint(accounts.bob), Err(Error::TooManyCheckpoints));
+        }
+
+        #[ink::test]
+        fn verify_invariants_detects_drift() {
+            let (mut voting, accounts) = setup();
+            set_caller(accounts.bob);
+            voting.vote(accounts.charlie, TypeVote::Like).unwrap();
+            set_caller(accounts.alice);
+            voting.remove_voter(accounts.charlie).unwrap();
+            assert!(voting.verify_invariants());
+
+            voting.total_reputation += 1;
+            assert!(!voting.verify_invariants());
+            voting.total_reputation -= 1;
+            voting.enabled_reputation += 1;
+            assert!(!voting.verify_invariants());
+        }
+
+        #[ink::test]
+        fn guardians_recover_admin() {
+            let accounts = test::default_accounts::<DefaultEnvironment>();
+            let (mut voting, _) = setup_with(Config {
+                guardians: vec![accounts.charlie, accounts.django, accounts.eve],
+                guardian_threshold: 2,
+                ..offline_config()
+            });
+            set_caller(accounts.bob);
+            assert_eq!(voting.recover_admin(accounts.frank), Err(Error::NotGuardian));
+
+            set_caller(accounts.charlie);
+            voting.recover_admin(accounts.frank).unwrap();
+            voting.recover_admin(accounts.frank).unwrap();
+            assert_eq!(voting.admin.address, accounts.alice);
+
+            set_caller(accounts.django);
+            voting.recover_admin(accounts.frank).unwrap();
+            assert_eq!(voting.admin.address, accounts.frank);
+            assert!(events()
+                .iter()
+                .any(|event| matches!(event, Event::AdminRecovered(AdminRecovered { new_admin, .. }) if *new_admin == accounts.frank)));
+
+            // Confirmations from the finished round do not carry over.
+            set_caller(accounts.eve);
+            voting.recover_admin(accounts.bob).unwrap();
+            assert_eq!(voting.admin.address, accounts.frank);
+        }
+
+        #[ink::test]
+        fn guardian_threshold_must_be_majority() {
+            let accounts = test::default_accounts::<DefaultEnvironment>();
+            for (guardians, threshold) in [(3, 1), (2, 1), (4, 2), (1, 0)] {
+                let config = Config {
+                    guardians: vec![accounts.bob, accounts.charlie, accounts.django, accounts.eve][..guardians].to_vec(),
+                    guardian_threshold: threshold,
+                    ..offline_config()
+                };
+                assert!(matches!(
+                    Voting::new_with_config(accounts.alice, Hash::from(CODE_HASH), config),
+                    Err(Error::InvalidGuardianThreshold)
+                ));
+            }
+        }
+
+        #[ink::test]
+        fn admin_can_vote_follows_config() {
+            let (mut voting, accounts) = setup_with(Config {
+                admin_can_vote: false,
+                ..offline_config()
+            });
+            voting.add_voter(accounts.alice).unwrap();
+            assert_eq!(voting.vote(accounts.bob, TypeVote::Like), Err(Error::AdminCannotVote));
+            assert_eq!(voting.can_i_vote(), Err(Error::AdminCannotVote));
+
+            voting.config.admin_can_vote = true;
+            assert_eq!(voting.vote(accounts.bob, TypeVote::Like), Ok(()));
+        }
+
+        #[ink::test]
+        fn admin_adjust_batch_applies_net_delta() {
+            let (mut voting, accounts) = setup();
+            voting
+                .admin_adjust_batch(vec![(accounts.bob, 10), (accounts.charlie, -3), (accounts.bob, 2)])
+                .unwrap();
+            assert_eq!(reputation(&voting, accounts.bob), 12);
+            assert_eq!(reputation(&voting, accounts.charlie), -3);
+            assert_eq!(voting.stats().total_votes, 9);
+            assert_eq!(voting.stats().total_reputation, 9);
+        }
+
+        #[ink::test]
+        fn admin_adjust_batch_rejects_bad_input() {
+            let (mut voting, accounts) = setup();
+            let batch = accounts_batch(51).into_iter().map(|who| (who, 1)).collect();
+            assert_eq!(voting.admin_adjust_batch(batch), Err(Error::BatchTooLarge));
+            assert_eq!(
+                voting.admin_adjust_batch(vec![(accounts.bob, i32::MAX), (accounts.charlie, 1)]),
+                Err(Error::Overflow)
+            );
+        }
+
+        #[ink::test]
+        fn admin_adjust_batch_keeps_total_votes_positive() {
+            let (mut voting, accounts) = setup();
+            assert_eq!(
+                voting.admin_adjust_batch(vec![(accounts.bob, 5), (accounts.charlie, -5)]),
+                Err(Error::InvalidAdjustment)
+            );
+        }
+
+        #[ink::test]
+        fn admin_adjust_batch_requires_admin() {
+            let (mut voting, accounts) = setup();
+            set_caller(accounts.bob);
+            assert_eq!(voting.admin_adjust_batch(vec![(accounts.bob, 1)]), Err(Error::NotIsAdmin));
+        }
+
+        #[ink::test]
+        fn config_returns_current_settings() {
+            let config = Config {
+                allow_self_vote: true,
+                moderator_threshold: 10,
+                ..offline_config()
+            };
+            let (mut voting, _) = setup_with(config.clone());
+            assert_eq!(voting.config(), config);
+
+            voting.set_max_votes_per_target(Some(4)).unwrap();
+            assert_eq!(voting.config().max_votes_per_target, Some(4));
+        }
+
+        #[ink::test]
+        fn power_share_over_enabled_voters() {
+            let (mut voting, accounts) = setup();
+            voting
+                .admin_adjust_batch(vec![(accounts.bob, 1), (accounts.charlie, 2), (accounts.django, 3)])
+                .unwrap();
+            assert_eq!(voting.power_share(accounts.bob), 1666);
+            assert_eq!(voting.power_share(accounts.charlie), 3333);
+            assert_eq!(voting.power_share(accounts.django), 5000);
+
+            voting.remove_voter(accounts.django).unwrap();
+            assert_eq!(voting.power_share(accounts.bob), 3333);
+            assert_eq!(voting.power_share(accounts.django), 0);
+            assert_eq!(voting.power_share(accounts.eve), 0);
+        }
+
+        #[ink::test]
+        fn auto_remove_below_floor() {
+            let (mut voting, accounts) = setup_with(Config {
+                auto_remove_below: Some(0),
+                ..offline_config()
+            });
+            voting.admin_adjust_reputation(accounts.django, 5).unwrap();
+
+            set_caller(accounts.bob);
+            voting.vote(accounts.django, TypeVote::Unlike).unwrap();
+            assert!(voting.enabled_voters.contains(accounts.django));
+
+            voting.vote(accounts.charlie, TypeVote::Unlike).unwrap();
+            assert!(!voting.enabled_voters.contains(accounts.charlie));
+            assert_eq!(voting.stats().voter_count, 2);
+            assert!(voting.verify_invariants());
+        }
+
+        #[ink::test]
+        fn claim_pending_mints_without_votes() {
+            let (mut voting, accounts) = setup_with(Config {
+                mint_enabled: true,
+                ..offline_config()
+            });
+            set_caller(accounts.bob);
+            assert_eq!(voting.claim_pending_mints(), Ok(0));
+        }
+
+        #[ink::test]
+        fn mint_setters_update_config() {
+            let (mut voting, accounts) = setup();
+            assert_eq!(voting.set_mint_enabled(true), Ok(()));
+            assert_eq!(voting.set_votes_per_mint(3), Ok(()));
+            assert!(voting.config().mint_enabled);
+            assert_eq!(voting.config().votes_per_mint, 3);
+
+            set_caller(accounts.bob);
+            assert_eq!(voting.set_mint_enabled(false), Err(Error::NotIsAdmin));
+            assert_eq!(voting.set_votes_per_mint(1), Err(Error::NotIsAdmin));
+        }
+
+        #[ink::test]
+        fn amend_vote_replaces_previous_vote() {
+            let (mut voting, accounts) = setup_with(Config {
+                amend_window_secs: 60,
+                ..offline_config()
+            });
+            set_caller(accounts.bob);
+            voting.vote(accounts.charlie, TypeVote::Like).unwrap();
+            assert_eq!(voting.amend_vote(accounts.charlie, TypeVote::Unlike), Ok(()));
+            assert_eq!(reputation(&voting, accounts.charlie), -1);
+            assert_eq!(voting.vote_breakdown(accounts.charlie), (0, 1));
+            assert_eq!(voting.stats().total_votes, 1);
+            assert!(voting.verify_invariants());
+
+            set_timestamp(59_999);
+            assert_eq!(voting.amend_vote(accounts.charlie, TypeVote::Like), Ok(()));
+            assert_eq!(reputation(&voting, accounts.charlie), 1);
+            set_timestamp(60_000);
+            assert_eq!(voting.amend_vote(accounts.charlie, TypeVote::Unlike), Err(Error::AmendWindowClosed));
+        }
+
+        #[ink::test]
+        fn amend_vote_needs_a_vote() {
+            let (mut voting, accounts) = setup_with(Config {
+                amend_window_secs: 60,
+                ..offline_config()
+            });
+            set_caller(accounts.bob);
+            assert_eq!(voting.amend_vote(accounts.charlie, TypeVote::Unlike), Err(Error::NoVoteToAmend));
+            set_caller(accounts.eve);
+            assert_eq!(voting.amend_vote(accounts.charlie, TypeVote::Unlike), Err(Error::NotIsVoter));
+        }
+
+        #[ink::test]
+        fn amend_vote_unavailable_under_commit_reveal() {
+            let (mut voting, accounts) = setup_with(Config {
+                commit_reveal: true,
+                amend_window_secs: 60,
+                ..offline_config()
+            });
+            set_caller(accounts.bob);
+            assert_eq!(voting.amend_vote(accounts.charlie, TypeVote::Unlike), Err(Error::CommitRevealRequired));
+        }
+
+        #[ink::test]
+        fn amend_vote_damps_downvotes() {
+            let (mut voting, accounts) = setup_with(Config {
+                amend_window_secs: 60,
+                downvote_damping_window: Some(60_000),
+                ..offline_config()
+            });
+            set_caller(accounts.django);
+            voting.vote(accounts.charlie, TypeVote::Unlike).unwrap();
+            set_caller(accounts.bob);
+            voting.vote(accounts.charlie, TypeVote::Like).unwrap();
+            assert_eq!(reputation(&voting, accounts.charlie), 0);
+
+            // Second distinct downvoter in the window: -1 is halved to 0.
+            voting.amend_vote(accounts.charlie, TypeVote::Unlike).unwrap();
+            assert_eq!(reputation(&voting, accounts.charlie), -1);
+        }
+
+        #[ink::test]
+        fn amend_vote_applies_reputation_floor() {
+            let (mut voting, accounts) = setup_with(Config {
+                amend_window_secs: 60,
+                auto_remove_below: Some(0),
+                ..offline_config()
+            });
+            set_caller(accounts.bob);
+            voting.vote(accounts.charlie, TypeVote::Like).unwrap();
+            voting.amend_vote(accounts.charlie, TypeVote::Unlike).unwrap();
+            assert_eq!(reputation(&voting, accounts.charlie), -1);
+            assert!(!voting.enabled_voters.contains(accounts.charlie));
+            assert_eq!(voting.stats().voter_count, 2);
+        }
+
+        #[ink::test]
+        fn amend_vote_checks_overflow() {
+            let (mut voting, accounts) = setup_with(Config {
+                amend_window_secs: 60,
+                ..offline_config()
+            });
+            set_caller(accounts.bob);
+            voting.vote(accounts.charlie, TypeVote::Like).unwrap();
+            let receipt = VoteReceipt { power: i32::MIN, ..voting.receipts.get((accounts.bob, accounts.charlie)).unwrap() };
+            voting.receipts.insert((accounts.bob, accounts.charlie), &receipt);
+            assert_eq!(voting.amend_vote(accounts.charlie, TypeVote::Like), Err(Error::Overflow));
+        }
+
+        #[ink::test]
+        fn vote_categorized_tallies_category() {
+            let (mut voting, accounts) = setup();
+            set_caller(accounts.bob);
+            voting.vote_categorized(accounts.charlie, TypeVote::Like, 3).unwrap();
+            set_caller(accounts.django);
+            voting.vote_categorized(accounts.charlie, TypeVote::Unlike, 4).unwrap();
+
+            assert_eq!(voting.reputation_in_category(accounts.charlie, 3), 1);
+            assert_eq!(voting.reputation_in_category(accounts.charlie, 4), -1);
+            assert_eq!(voting.reputation_in_category(accounts.charlie, 5), 0);
+            assert_eq!(reputation(&voting, accounts.charlie), 0);
+        }
+
+        #[ink::test]
+        fn amend_vote_moves_category_tally() {
+            let (mut voting, accounts) = setup_with(Config {
+                amend_window_secs: 60,
+                ..offline_config()
+            });
+            set_caller(accounts.bob);
+            voting.vote_categorized(accounts.charlie, TypeVote::Like, 3).unwrap();
+            voting.amend_vote(accounts.charlie, TypeVote::Unlike).unwrap();
+            assert_eq!(voting.reputation_in_category(accounts.charlie, 3), -1);
+            assert_eq!(reputation(&voting, accounts.charlie), -1);
+
+            voting.vote(accounts.django, TypeVote::Like).unwrap();
+            voting.amend_vote(accounts.django, TypeVote::Unlike).unwrap();
+            assert_eq!(voting.reputation_in_category(accounts.django, 3), 0);
+        }
+
+        #[ink::test]
+        fn distinct_voters_counts_each_caller_once() {
+            let (mut voting, accounts) = setup();
+            set_caller(accounts.bob);
+            voting.vote(accounts.charlie, TypeVote::Like).unwrap();
+            voting.vote(accounts.charlie, TypeVote::Unlike).unwrap();
+            set_caller(accounts.django);
+            voting.vote(accounts.charlie, TypeVote::Like).unwrap();
+
+            assert_eq!(voting.distinct_voters_of(accounts.charlie), 2);
+            assert_eq!(voting.distinct_voters_of(accounts.bob), 0);
+        }
+
+        #[ink::test]
+        fn max_voters_caps_enabled_voters() {
+            let (mut voting, accounts) = setup();
+            voting.set_max_voters(Some(3)).unwrap();
+            assert_eq!(voting.add_voter(accounts.eve), Err(Error::VoterCapReached));
+
+            voting.remove_voter(accounts.bob).unwrap();
+            assert_eq!(voting.add_voter(accounts.eve), Ok(()));
+
+            voting.set_max_voters(Some(1)).unwrap();
+            assert_eq!(voting.stats().voter_count, 3);
+            assert_eq!(voting.add_voter(accounts.bob), Err(Error::VoterCapReached));
+        }
+
+        #[ink::test]
+        fn set_max_voters_requires_admin() {
+            let (mut voting, accounts) = setup();
+            set_caller(accounts.bob);
+            assert_eq!(voting.set_max_voters(Some(3)), Err(Error::NotIsAdmin));
+        }
+
+        #[ink::test]
+        fn average_reputation_over_enabled_voters() {
+            let (mut voting, accounts) = setup();
+            assert_eq!(voting.average_reputation(), 0);
+            voting
+                .admin_adjust_batch(vec![(accounts.bob, 3), (accounts.charlie, 6), (accounts.django, 9)])
+                .unwrap();
+            assert_eq!(voting.average_reputation(), 6);
+
+            voting.remove_voter(accounts.django).unwrap();
+            assert_eq!(voting.average_reputation(), 4);
+
+            voting.remove_voters(vec![accounts.bob, accounts.charlie]).unwrap();
+            assert_eq!(voting.average_reputation(), 0);
+        }
+
+        #[ink::test]
+        fn average_reputation_truncates_toward_zero() {
+            let (mut voting, accounts) = setup();
+            voting
+                .admin_adjust_batch(vec![(accounts.bob, 10), (accounts.charlie, -3), (accounts.django, -4)])
+                .unwrap();
+            voting.remove_voter(accounts.bob).unwrap();
+            assert_eq!(voting.average_reputation(), -3);
+        }
+
+        #[ink::test]
+        fn trait_add_and_remove_voter() {
+            let (mut voting, accounts) = setup();
+            assert_eq!(Votingtraits::add_voter(&mut voting, accounts.eve), Ok(()));
+            assert_eq!(Votingtraits::add_voter(&mut voting, accounts.eve), Err(Error::VoterAlreadyExists));
+            assert_eq!(Votingtraits::remove_voter(&mut voting, accounts.eve), Ok(()));
+            assert_eq!(Votingtraits::remove_voter(&mut voting, accounts.eve), Err(Error::VoterNotExist));
+
+            set_caller(accounts.bob);
+            assert_eq!(Votingtraits::add_voter(&mut voting, accounts.eve), Err(Error::NotIsAdmin));
+        }
+
+        #[ink::test]
+        fn trait_vote_propagates_result() {
+            let (mut voting, accounts) = setup();
+            set_caller(accounts.bob);
+            assert_eq!(Votingtraits::vote(&mut voting, accounts.bob, TypeVote::Like), Err(Error::NotVoteItSelf));
+            assert_eq!(Votingtraits::vote(&mut voting, accounts.charlie, TypeVote::Like), Ok(()));
+            assert_eq!(reputation(&voting, accounts.charlie), 1);
+        }
+
+        #[ink::test]
+        fn trait_get_balance_checks_caller() {
+            let (voting, accounts) = setup();
+            assert_eq!(Votingtraits::get_balance(&voting, accounts.bob), Err(Error::MustBeItSelf));
+            set_caller(accounts.eve);
+            assert_eq!(Votingtraits::get_balance(&voting, accounts.eve), Err(Error::VoterNotExist));
+        }
+
+        #[ink::test]
+        fn downvotes_require_reason() {
+            let (mut voting, accounts) = setup_with(Config {
+                require_downvote_reason: true,
+                ..offline_config()
+            });
+            let reason = Hash::from([9; 32]);
+            set_caller(accounts.bob);
+            assert_eq!(voting.vote(accounts.charlie, TypeVote::Unlike), Err(Error::ReasonRequired));
+            assert_eq!(voting.vote_with_reason(accounts.charlie, TypeVote::Unlike, None), Err(Error::ReasonRequired));
+            assert_eq!(voting.vote(accounts.charlie, TypeVote::Like), Ok(()));
+            assert_eq!(voting.vote_with_reason(accounts.charlie, TypeVote::Unlike, Some(reason)), Ok(()));
+
+            let reasons: Vec<_> = events()
+                .into_iter()
+                .filter_map(|event| match event {
+                    Event::VoteReason(VoteReason { voter_id, reason, .. }) => Some((voter_id, reason)),
+                    _ => None,
+                })
+                .collect();
+            assert_eq!(reasons, vec![(accounts.charlie, reason)]);
+        }
+
+        #[ink::test]
+        fn amend_to_unlike_requires_reason() {
+            let (mut voting, accounts) = setup_with(Config {
+                require_downvote_reason: true,
+                amend_window_secs: 60,
+                ..offline_config()
+            });
+            set_caller(accounts.bob);
+            voting.vote(accounts.charlie, TypeVote::Like).unwrap();
+            assert_eq!(voting.amend_vote(accounts.charlie, TypeVote::Unlike), Err(Error::ReasonRequired));
+            assert_eq!(reputation(&voting, accounts.charlie), 1);
+        }
+
+        #[ink::test]
+        fn downvote_reason_incompatible_with_commit_reveal() {
+            let accounts = test::default_accounts::<DefaultEnvironment>();
+            let config = Config {
+                require_downvote_reason: true,
+                commit_reveal: true,
+                ..offline_config()
+            };
+            assert!(matches!(
+                Voting::new_with_config(accounts.alice, Hash::from(CODE_HASH), config),
+                Err(Error::IncompatibleConfig)
+            ));
+        }
+
+        #[ink::test]
+        fn cooldown_applies_between_votes() {
+            let (mut voting, accounts) = setup_with(Config {
+                cooldown_secs: 10,
+                ..offline_config()
+            });
+            set_caller(accounts.bob);
+            voting.vote(accounts.charlie, TypeVote::Like).unwrap();
+            set_timestamp(9_999);
+            assert_eq!(voting.vote(accounts.django, TypeVote::Like), Err(Error::CooldownActive));
+            set_timestamp(10_000);
+            assert_eq!(voting.vote(accounts.django, TypeVote::Like), Ok(()));
+        }
+
+        #[ink::test]
+        fn cooldown_scales_with_band() {
+            let (mut voting, accounts) = setup_with(Config {
+                cooldown_secs: 10,
+                cooldown_scales_with_band: true,
+                ..offline_config()
+            });
+            voting.admin_adjust_reputation(accounts.bob, 100).unwrap();
+            set_caller(accounts.bob);
+            voting.vote(accounts.charlie, TypeVote::Like).unwrap();
+            set_caller(accounts.django);
+            voting.vote(accounts.charlie, TypeVote::Like).unwrap();
+
+            set_timestamp(10_000);
+            assert_eq!(voting.vote(accounts.charlie, TypeVote::Like), Ok(()));
+            set_caller(accounts.bob);
+            assert_eq!(voting.vote(accounts.charlie, TypeVote::Like), Err(Error::CooldownActive));
+            set_timestamp(30_000);
+            assert_eq!(voting.vote(accounts.charlie, TypeVote::Like), Ok(()));
+        }
+
+        #[ink::test]
+        fn sentiment_ignores_vote_power() {
+            let (mut voting, accounts) = setup();
+            voting.admin_adjust_reputation(accounts.bob, 100).unwrap();
+            set_caller(accounts.bob);
+            voting.vote(accounts.charlie, TypeVote::Like).unwrap();
+            set_caller(accounts.django);
+            voting.vote(accounts.charlie, TypeVote::Unlike).unwrap();
+            voting.vote(accounts.bob, TypeVote::Unlike).unwrap();
+
+            assert_eq!(reputation(&voting, accounts.charlie), 2);
+            assert_eq!(voting.sentiment(accounts.charlie), 0);
+            assert_eq!(voting.sentiment(accounts.bob), -1);
+            assert_eq!(voting.sentiment(accounts.django), 0);
+        }
+
+        #[ink::test]
+        fn blocked_pairs_cannot_vote_for_each_other() {
+            let (mut voting, accounts) = setup();
+            voting.block_pair(accounts.bob, accounts.charlie).unwrap();
+
+            set_caller(accounts.bob);
+            assert_eq!(voting.vote(accounts.charlie, TypeVote::Like), Err(Error::PairBlocked));
+            assert_eq!(voting.vote(accounts.django, TypeVote::Like), Ok(()));
+            set_caller(accounts.charlie);
+            assert_eq!(voting.vote(accounts.bob, TypeVote::Like), Err(Error::PairBlocked));
+            assert_eq!(voting.block_pair(accounts.charlie, accounts.django), Err(Error::NotIsAdmin));
+
+            set_caller(accounts.alice);
+            voting.unblock_pair(accounts.charlie, accounts.bob).unwrap();
+            set_caller(accounts.bob);
+            assert_eq!(voting.vote(accounts.charlie, TypeVote::Like), Ok(()));
+        }
+
+        #[ink::test]
+        fn last_activity_tracks_latest_vote() {
+            let (mut voting, accounts) = setup();
+            assert_eq!(voting.last_activity(), 0);
+
+            set_timestamp(7000);
+            set_caller(accounts.bob);
+            voting.vote(accounts.charlie, TypeVote::Like).unwrap();
+            set_timestamp(8000);
+            set_caller(accounts.alice);
+            voting.admin_adjust_reputation(accounts.bob, 1).unwrap();
+            assert_eq!(voting.last_activity(), 7000);
+        }
+
+        #[ink::test]
+        fn redeem_reputation_checks_cost() {
+            let (mut voting, accounts) = setup();
+            voting.admin_adjust_reputation(accounts.bob, 3).unwrap();
+            set_caller(accounts.eve);
+            assert_eq!(voting.redeem_reputation(1), Err(Error::NotIsVoter));
+            set_caller(accounts.bob);
+            assert_eq!(voting.redeem_reputation(0), Err(Error::InvalidCost));
+            assert_eq!(voting.redeem_reputation(-1), Err(Error::InvalidCost));
+            assert_eq!(voting.redeem_reputation(4), Err(Error::InsufficientReputation));
+        }
+
+        #[ink::test]
+        fn redeem_reputation_keeps_total_votes_positive() {
+            let (mut voting, accounts) = setup();
+            voting.admin_adjust_batch(vec![(accounts.bob, 10), (accounts.charlie, -5)]).unwrap();
+            set_caller(accounts.bob);
+            assert_eq!(voting.redeem_reputation(5), Err(Error::InvalidAdjustment));
+            assert_eq!(voting.stats().total_votes, 5);
+            assert_eq!(voting.band_of(10), 3);
+        }
+
+        #[ink::test]
+        fn export_state_is_clamped_to_max_page() {
+            let (mut voting, _) = setup();
+            let extra = accounts_batch(70);
+            voting.add_voters(extra[..35].to_vec()).unwrap();
+            voting.add_voters(extra[35..].to_vec()).unwrap();
+
+            assert_eq!(voting.export_state(0, u32::MAX).unwrap().len(), MAX_PAGE as usize);
+            assert_eq!(voting.export_state(MAX_PAGE, MAX_PAGE).unwrap().len(), 73 - MAX_PAGE as usize);
+            assert!(voting.export_state(u32::MAX, u32::MAX).unwrap().is_empty());
+        }
+
+        #[ink::test]
+        fn migrate_voter_moves_membership() {
+            let (mut voting, accounts) = setup();
+            voting.admin_adjust_reputation(accounts.bob, 5).unwrap();
+            voting.migrate_voter(accounts.bob, accounts.eve).unwrap();
+
+            assert_eq!(reputation(&voting, accounts.bob), 0);
+            assert_eq!(reputation(&voting, accounts.eve), 5);
+            assert_eq!(voting.stats().voter_count, 3);
+            assert!(voting.verify_invariants());
+
+            set_caller(accounts.bob);
+            assert_eq!(voting.vote(accounts.charlie, TypeVote::Like), Err(Error::NotIsVoter));
+            set_caller(accounts.eve);
+            assert_eq!(voting.vote(accounts.charlie, TypeVote::Like), Ok(()));
+
+            let topics = topics_of(|event| matches!(event, Event::VoterMigrated(_)));
+            assert_eq!(topics.len(), 1);
+            assert!(topics[0].contains(&topic("VoterMigrated", "old", &accounts.bob)));
+            assert!(topics[0].contains(&topic("VoterMigrated", "new", &accounts.eve)));
+        }
+
+        #[ink::test]
+        fn migrate_voter_adds_to_existing_reputation() {
+            let (mut voting, accounts) = setup();
+            voting.admin_adjust_batch(vec![(accounts.bob, 5), (accounts.eve, 2)]).unwrap();
+            voting.migrate_voter(accounts.bob, accounts.eve).unwrap();
+            assert_eq!(reputation(&voting, accounts.eve), 7);
+            assert!(voting.verify_invariants());
+        }
+
+        #[ink::test]
+        fn migrate_voter_rejects_bad_accounts() {
+            let (mut voting, accounts) = setup();
+            assert_eq!(voting.migrate_voter(accounts.eve, accounts.frank), Err(Error::VoterNotExist));
+            assert_eq!(voting.migrate_voter(accounts.bob, accounts.charlie), Err(Error::VoterAlreadyExists));
+            set_caller(accounts.bob);
+            assert_eq!(voting.migrate_voter(accounts.bob, accounts.eve), Err(Error::NotIsAdmin));
+        }
+
+        #[ink::test]
+        fn migrate_voter_private_topics() {
+            let (mut voting, accounts) = setup_with(Config {
+                private_topics: true,
+                ..offline_config()
+            });
+            voting.migrate_voter(accounts.bob, accounts.eve).unwrap();
+
+            assert!(topics_of(|event| matches!(event, Event::VoterMigrated(_))).is_empty());
+            let topics = topics_of(|event| matches!(event, Event::PrivateVoterMigrated(_)));
+            assert_eq!(topics.len(), 1);
+            assert_eq!(topics[0].len(), 1);
+        }
+
+        #[ink::test]
+        fn migrate_voter_keeps_cooldown() {
+            let (mut voting, accounts) = setup_with(Config {
+                cooldown_secs: 10,
+                ..offline_config()
+            });
+            set_caller(accounts.bob);
+            voting.vote(accounts.charlie, TypeVote::Like).unwrap();
+            set_caller(accounts.alice);
+            voting.migrate_voter(accounts.bob, accounts.eve).unwrap();
+
+            assert_eq!(voting.last_vote_at(accounts.eve), 0);
+            assert_eq!(voting.cooldown_remaining(accounts.eve), 10);
+            set_caller(accounts.eve);
+            assert_eq!(voting.vote(accounts.django, TypeVote::Like), Err(Error::CooldownActive));
+            set_timestamp(10_000);
+            assert_eq!(voting.vote(accounts.django, TypeVote::Like), Ok(()));
+        }
+
+        #[ink::test]
+        fn migrate_voter_moves_counters() {
+            let (mut voting, accounts) = setup();
+            set_caller(accounts.bob);
+            voting.vote(accounts.charlie, TypeVote::Like).unwrap();
+            set_caller(accounts.django);
+            voting.vote(accounts.bob, TypeVote::Unlike).unwrap();
+            set_caller(accounts.alice);
+            voting.migrate_voter(accounts.bob, accounts.eve).unwrap();
+
+            assert_eq!(voting.votes_cast.get(accounts.eve), Some(1));
+            assert_eq!(voting.votes_cast.get(accounts.bob), None);
+            assert_eq!(voting.vote_breakdown(accounts.eve), (0, 1));
+            assert_eq!(voting.vote_breakdown(accounts.bob), (0, 0));
+        }
+
+        #[ink::test]
+        fn restrict_downvotes_needs_band() {
+            let (mut voting, accounts) = setup_with(Config {
+                restrict_downvotes: true,
+                ..offline_config()
+            });
+            voting.admin_adjust_reputation(accounts.django, 10).unwrap();
+
+            set_caller(accounts.bob);
+            assert_eq!(voting.vote(accounts.charlie, TypeVote::Unlike), Err(Error::CannotDownvote));
+            assert_eq!(voting.vote(accounts.charlie, TypeVote::Like), Ok(()));
+            set_caller(accounts.django);
+            assert_eq!(voting.vote(accounts.charlie, TypeVote::Unlike), Ok(()));
+        }
+
+        #[ink::test]
+        fn simulate_vote_matches_vote() {
+            let (mut voting, accounts) = setup();
+            voting.admin_adjust_reputation(accounts.bob, 10).unwrap();
+            set_caller(accounts.bob);
+            let simulated = voting.simulate_vote(accounts.charlie, TypeVote::Like);
+            assert_eq!(simulated, SimResult { new_reputation: 3, would_mint: false, error: None });
+
+            voting.vote(accounts.charlie, TypeVote::Like).unwrap();
+            assert_eq!(reputation(&voting, accounts.charlie), simulated.new_reputation);
+        }
+
+        #[ink::test]
+        fn simulate_vote_reports_error() {
+            let (mut voting, accounts) = setup();
+            voting.admin_adjust_reputation(accounts.bob, 4).unwrap();
+            set_caller(accounts.bob);
+            assert_eq!(
+                voting.simulate_vote(accounts.bob, TypeVote::Like),
+                SimResult { new_reputation: 4, would_mint: false, error: Some(Error::NotVoteItSelf) }
+            );
+            set_caller(accounts.eve);
+            assert_eq!(voting.simulate_vote(accounts.bob, TypeVote::Like).error, Some(Error::NotIsVoter));
+        }
+
+        #[ink::test]
+        fn cooldown_remaining_rounds_up() {
+            let (mut voting, accounts) = setup_with(Config {
+                cooldown_secs: 10,
+                ..offline_config()
+            });
+            assert_eq!(voting.cooldown_remaining(accounts.bob), 0);
+
+            set_caller(accounts.bob);
+            voting.vote(accounts.charlie, TypeVote::Like).unwrap();
+            assert_eq!(voting.cooldown_remaining(accounts.bob), 10);
+            set_timestamp(4500);
+            assert_eq!(voting.cooldown_remaining(accounts.bob), 6);
+            set_timestamp(10_000);
+            assert_eq!(voting.cooldown_remaining(accounts.bob), 0);
+            assert_eq!(voting.cooldown_remaining(accounts.charlie), 0);
+        }
+
+        #[ink::test]
+        fn category_caps_limit_votes_per_caller() {
+            let (mut voting, accounts) = setup();
+            voting.set_category_cap(1, Some(1)).unwrap();
+
+            set_caller(accounts.bob);
+            assert_eq!(voting.vote_categorized(accounts.charlie, TypeVote::Like, 1), Ok(()));
+            assert_eq!(voting.vote_categorized(accounts.django, TypeVote::Like, 1), Err(Error::CategoryCapReached));
+            assert_eq!(voting.vote_categorized(accounts.django, TypeVote::Like, 2), Ok(()));
+            set_caller(accounts.django);
+            assert_eq!(voting.vote_categorized(accounts.charlie, TypeVote::Like, 1), Ok(()));
+            assert_eq!(voting.set_category_cap(1, None), Err(Error::NotIsAdmin));
+
+            set_caller(accounts.alice);
+            voting.set_category_cap(1, None).unwrap();
+            set_caller(accounts.bob);
+            assert_eq!(voting.vote_categorized(accounts.django, TypeVote::Like, 1), Ok(()));
+        }
+
+        #[ink::test]
+        fn nft_code_hash_returns_instantiated_hash() {
+            let (voting, _) = setup();
+            assert_eq!(voting.nft_code_hash(), Hash::from(CODE_HASH));
+        }
+
+        #[ink::test]
+        fn vote_quadratic_charges_square() {
+            let (mut voting, accounts) = setup();
+            voting.admin_adjust_reputation(accounts.bob, 9).unwrap();
+
+            set_caller(accounts.bob);
+            assert_eq!(voting.vote_quadratic(accounts.charlie, 3), Ok(()));
+            assert_eq!(reputation(&voting, accounts.bob), 0);
+            assert_eq!(reputation(&voting, accounts.charlie), 3);
+            assert_eq!(voting.vote_breakdown(accounts.charlie), (1, 0));
+            assert_eq!(voting.stats().total_votes, 12);
+            assert_eq!(voting.stats().total_reputation, 3);
+            assert!(voting.verify_invariants());
+        }
+
+        #[ink::test]
+        fn vote_quadratic_rejects_bad_cost() {
+            let (mut voting, accounts) = setup();
+            voting.admin_adjust_reputation(accounts.bob, 3).unwrap();
+
+            set_caller(accounts.bob);
+            assert_eq!(voting.vote_quadratic(accounts.charlie, 0), Err(Error::InvalidCost));
+            assert_eq!(voting.vote_quadratic(accounts.charlie, 2), Err(Error::InsufficientReputation));
+            assert_eq!(voting.vote_quadratic(accounts.charlie, u32::MAX), Err(Error::Overflow));
+            assert_eq!(voting.vote_quadratic(accounts.bob, 1), Err(Error::NotVoteItSelf));
+            assert_eq!(reputation(&voting, accounts.bob), 3);
+        }
+
+        #[ink::test]
+        fn membership_events_record_caller() {
+            let (mut voting, accounts) = setup_with(Config {
+                auto_remove_below: Some(0),
+                ..offline_config()
+            });
+            set_caller(accounts.bob);
+            voting.vote(accounts.charlie, TypeVote::Unlike).unwrap();
+
+            let added: Vec<_> = events()
+                .into_iter()
+                .filter_map(|event| match event {
+                    Event::NewVoter(NewVoter { voter_id, by, .. }) => Some((voter_id, by)),
+                    _ => None,
+                })
+                .collect();
+            assert_eq!(
+                added,
+                vec![(accounts.bob, accounts.alice), (accounts.charlie, accounts.alice), (accounts.django, accounts.alice)]
+            );
+            let removed: Vec<_> = events()
+                .into_iter()
+                .filter_map(|event| match event {
+                    Event::RemoveVoter(RemoveVoter { voter_id, by, .. }) => Some((voter_id, by)),
+                    _ => None,
+                })
+                .collect();
+            assert_eq!(removed, vec![(accounts.charlie, accounts.bob)]);
+        }
+
+        #[ink::test]
+        fn set_nft_contract_locked_after_first_mint() {
+            let (mut voting, accounts) = setup();
+            voting.first_mint_done = true;
+            assert_eq!(voting.set_nft_contract(Hash::from(CODE_HASH), Vec::new()), Err(Error::NftContractLocked));
+
+            set_caller(accounts.bob);
+            assert_eq!(voting.set_nft_contract(Hash::from(CODE_HASH), Vec::new()), Err(Error::NotIsAdmin));
+        }
+
+        #[ink::test]
+        fn new_voters_wait_min_blocks() {
+            let (mut voting, accounts) = setup_with(Config {
+                min_blocks_after_join: 2,
+                ..offline_config()
+            });
+            set_caller(accounts.bob);
+            assert_eq!(voting.vote(accounts.charlie, TypeVote::Like), Err(Error::AccountTooNew));
+            test::advance_block::<DefaultEnvironment>();
+            assert_eq!(voting.can_i_vote(), Err(Error::AccountTooNew));
+            test::advance_block::<DefaultEnvironment>();
+            assert_eq!(voting.vote(accounts.charlie, TypeVote::Like), Ok(()));
+        }
+
+        #[ink::test]
+        fn version_info_reports_constants() {
+            let (voting, _) = setup();
+            assert_eq!(voting.version_info(), (STORAGE_VERSION, BUILD_TAG));
+        }
+
+        #[ink::test]
+        fn my_vote_returns_receipt() {
+            let (mut voting, accounts) = setup();
+            set_timestamp(3000);
+            set_caller(accounts.bob);
+            assert_eq!(voting.my_vote(accounts.charlie), None);
+            voting.vote(accounts.charlie, TypeVote::Like).unwrap();
+            assert_eq!(voting.my_vote(accounts.charlie), Some((TypeVote::Like, 1, 3000)));
+
+            set_timestamp(4000);
+            voting.vote(accounts.charlie, TypeVote::Unlike).unwrap();
+            assert_eq!(voting.my_vote(accounts.charlie), Some((TypeVote::Unlike, -1, 4000)));
+            set_caller(accounts.django);
+            assert_eq!(voting.my_vote(accounts.charlie), None);
+        }
+
+        #[ink::test]
+        fn auto_pause_on_block_volume() {
+            let (mut voting, accounts) = setup_with(Config {
+                auto_pause_threshold: Some(2),
+                ..offline_config()
+            });
+            set_caller(accounts.bob);
+            voting.vote(accounts.charlie, TypeVote::Like).unwrap();
+            voting.vote(accounts.django, TypeVote::Like).unwrap();
+            assert!(!voting.is_paused());
+            assert_eq!(voting.vote(accounts.charlie, TypeVote::Like), Ok(()));
+            assert!(voting.is_paused());
+            assert!(events().iter().any(|event| matches!(event, Event::AutoPaused(AutoPaused { votes: 3, .. }))));
+
+            test::advance_block::<DefaultEnvironment>();
+            assert_eq!(voting.vote(accounts.charlie, TypeVote::Like), Err(Error::Paused));
+            assert_eq!(voting.unpause(), Err(Error::NotIsAdmin));
+            set_caller(accounts.alice);
+            voting.unpause().unwrap();
+            set_caller(accounts.bob);
+            assert_eq!(voting.vote(accounts.charlie, TypeVote::Like), Ok(()));
+        }
+
+        #[ink::test]
+        fn auto_pause_counts_quadratic_votes() {
+            let (mut voting, accounts) = setup_with(Config {
+                auto_pause_threshold: Some(1),
+                ..offline_config()
+            });
+            voting.admin_adjust_reputation(accounts.bob, 1).unwrap();
+            set_caller(accounts.bob);
+            voting.vote(accounts.charlie, TypeVote::Like).unwrap();
+            voting.vote_quadratic(accounts.django, 1).unwrap();
+            assert!(voting.is_paused());
+        }
+
+        #[ink::test]
+        fn reputation_gini_measures_concentration() {
+            let (mut voting, accounts) = setup();
+            assert_eq!(voting.reputation_gini(), 0);
+
+            voting
+                .admin_adjust_batch(vec![(accounts.bob, 5), (accounts.charlie, 5), (accounts.django, 5)])
+                .unwrap();
+            assert_eq!(voting.reputation_gini(), 0);
+
+            voting
+                .admin_adjust_batch(vec![(accounts.bob, 10), (accounts.charlie, -5), (accounts.django, -5)])
+                .unwrap();
+            assert_eq!(voting.reputation_gini(), 6666);
+        }
+
+        #[ink::test]
+        fn frozen_reputation_ignores_votes() {
+            let (mut voting, accounts) = setup();
+            voting.freeze_reputation(accounts.charlie).unwrap();
+            assert!(voting.is_frozen(accounts.charlie));
+
+            set_caller(accounts.bob);
+            assert_eq!(voting.simulate_vote(accounts.charlie, TypeVote::Like).new_reputation, 0);
+            assert_eq!(voting.vote(accounts.charlie, TypeVote::Like), Ok(()));
+            assert_eq!(reputation(&voting, accounts.charlie), 0);
+            assert_eq!(voting.vote_breakdown(accounts.charlie), (1, 0));
+            assert_eq!(voting.vote_quadratic(accounts.charlie, 1), Err(Error::ReputationFrozen));
+
+            set_caller(accounts.alice);
+            voting.unfreeze_reputation(accounts.charlie).unwrap();
+            assert!(!voting.is_frozen(accounts.charlie));
+            set_caller(accounts.bob);
+            voting.vote(accounts.charlie, TypeVote::Like).unwrap();
+            assert_eq!(reputation(&voting, accounts.charlie), 1);
+        }
+
+        #[ink::test]
+        fn freeze_requires_admin() {
+            let (mut voting, accounts) = setup();
+            set_caller(accounts.bob);
+            assert_eq!(voting.freeze_reputation(accounts.charlie), Err(Error::NotIsAdmin));
+            assert_eq!(voting.unfreeze_reputation(accounts.charlie), Err(Error::NotIsAdmin));
+        }
+
+        #[ink::test]
+        fn migrate_voter_keeps_freeze() {
+            let (mut voting, accounts) = setup();
+            voting.freeze_reputation(accounts.charlie).unwrap();
+            voting.migrate_voter(accounts.charlie, accounts.eve).unwrap();
+            assert!(!voting.is_frozen(accounts.charlie));
+            assert!(voting.is_frozen(accounts.eve));
+
+            set_caller(accounts.bob);
+            voting.vote(accounts.eve, TypeVote::Like).unwrap();
+            assert_eq!(reputation(&voting, accounts.eve), 0);
+        }
+
+        #[ink::test]
+        fn simulate_vote_applies_damping() {
+            let (mut voting, accounts) = setup_with(Config {
+                downvote_damping_window: Some(1000),
+                stake_unit: 1,
+                ..offline_config()
+            });
+            stake(&mut voting, accounts.bob, 7);
+            voting.vote(accounts.django, TypeVote::Unlike).unwrap();
+
+            stake(&mut voting, accounts.charlie, 7);
+            let simulated = voting.simulate_vote(accounts.django, TypeVote::Unlike);
+            assert_eq!(simulated.new_reputation, -12);
+            voting.vote(accounts.django, TypeVote::Unlike).unwrap();
+            assert_eq!(reputation(&voting, accounts.django), -12);
+        }
+
+        #[ink::test]
+        fn banded_reputation_hides_raw_figure() {
+            let (mut voting, accounts) = setup();
+            voting
+                .admin_adjust_batch(vec![(accounts.django, 70), (accounts.charlie, 35), (accounts.bob, -5)])
+                .unwrap();
+            assert_eq!(voting.banded_reputation(accounts.bob), 0);
+            assert_eq!(voting.banded_reputation(accounts.charlie), 2);
+            assert_eq!(voting.banded_reputation(accounts.django), 3);
+            assert_eq!(voting.banded_reputation(accounts.eve), 1);
+        }
+    }
+
+    #[cfg(all(test, feature = "e2e-tests"))]
+    mod e2e_tests {
+        use super::*;
+        use ink_e2e::subxt::blocks::ExtrinsicEvents;
+        use ink_e2e::AccountKeyring::{Alice, Bob, Charlie, Dave};
+        use ink_e2e::{build_message, Keypair, PolkadotConfig};
+        use mock_oracle::MockOracleRef;
+
+        type E2EResult<T> = std::result::Result<T, Box<dyn std::error::Error>>;
+        type E2EClient = ink_e2e::Client<PolkadotConfig, DefaultEnvironment>;
+        type Event = <Voting as ink::reflect::ContractEventBase>::Type;
+
+        /// Contract instantiated by `deployer` in the transaction that produced `events`.
+        fn instantiated_by(events: &ExtrinsicEvents<PolkadotConfig>, deployer: AccountId) -> Option<AccountId> {
+            events
+                .iter()
+                .filter_map(Result::ok)
+                .filter(|event| event.pallet_name() == "Contracts" && event.variant_name() == "Instantiated")
+                .filter_map(|event| <(AccountId, AccountId)>::decode(&mut event.field_bytes()).ok())
+                .find(|(from, _)| *from == deployer)
+                .map(|(_, contract)| contract)
+        }
+
+        /// Deploys Voting with Alice as admin. Returns it together with its badge collection.
+        async fn deploy(client: &mut E2EClient, config: Config) -> (AccountId, AccountId) {
+            let code_hash = client
+                .upload("psp34", &ink_e2e::alice(), None)
+                .await
+                .expect("psp34 upload failed")
+                .code_hash;
+            let constructor = VotingRef::new_with_config(ink_e2e::account_id(Alice), code_hash, config);
+            let instantiated = client
+                .instantiate("voting", &ink_e2e::alice(), constructor, 0, None)
+                .await
+                .expect("voting instantiate failed");
+            let voting = instantiated.account_id;
+            let collection = instantiated_by(&instantiated.events, voting).expect("no badge collection");
+            (voting, collection)
+        }
+
+        /// `deploy` with Bob, Charlie and Dave added as voters.
+        async fn setup(client: &mut E2EClient, config: Config) -> (AccountId, AccountId) {
+            let (voting, collection) = deploy(client, config).await;
+            let voters = vec![ink_e2e::account_id(Bob), ink_e2e::account_id(Charlie), ink_e2e::account_id(Dave)];
+            let add_voters = build_message::<VotingRef>(voting).call(|voting| voting.add_voters(voters.clone()));
+            let added = client.call(&ink_e2e::alice(), add_voters, 0, None).await.expect("add_voters failed");
+            assert_eq!(added.return_value(), Ok(()));
+            (voting, collection)
+        }
+
+        /// Events `voting` emitted in the transaction that produced `events`.
+        fn emitted_by(events: &ExtrinsicEvents<PolkadotConfig>, voting: AccountId) -> Vec<Event> {
+            events
+                .iter()
+                .filter_map(Result::ok)
+                .filter(|event| event.pallet_name() == "Contracts" && event.variant_name() == "ContractEmitted")
+                .filter_map(|event| <(AccountId, Vec<u8>)>::decode(&mut event.field_bytes()).ok())
+                .filter(|(contract, _)| *contract == voting)
+                .filter_map(|(_, data)| Event::decode(&mut &data[..]).ok())
+                .collect()
+        }
+
+        /// Casts a `Like` for `target` as `signer`, returning what `vote` returned.
+        async fn vote(client: &mut E2EClient, voting: AccountId, signer: &Keypair, target: AccountId) -> Result<(), Error> {
+            let vote = build_message::<VotingRef>(voting).call(|voting| voting.vote(target, TypeVote::Like));
+            let result = client.call_dry_run(signer, &vote, 0, None).await.return_value();
+            if result.is_ok() {
+                client.call(signer, vote, 0, None).await.expect("vote failed");
+            }
+            result
+        }
+
+        /// Badges held by `who` in the current collection, as listed by `my_tokens`.
+        async fn tokens(client: &mut E2EClient, voting: AccountId, who: AccountId) -> Vec<Id> {
+            let my_tokens = build_message::<VotingRef>(voting).call(|voting| voting.my_tokens(who));
+            client.call_dry_run(&ink_e2e::alice(), &my_tokens, 0, None).await.return_value()
+        }
+
+        /// `signer`'s own reputation, read by `signer` as `get_reputation` requires.
+        async fn reputation(client: &mut E2EClient, voting: AccountId, signer: &Keypair) -> i32 {
+            let who = AccountId::from(signer.public_key().0);
+            let get_reputation = build_message::<VotingRef>(voting).call(|voting| voting.get_reputation(who));
+            client.call_dry_run(signer, &get_reputation, 0, None).await.return_value().expect("not a voter")
+        }
+
+        fn commitment(voter_id: AccountId, value: TypeVote, salt: [u8; 32]) -> [u8; 32] {
+            let mut hash = [0; 32];
+            ink::env::hash_encoded::<Keccak256, _>(&(voter_id, value, salt), &mut hash);
+            hash
+        }
+
+        /// Casts a `Like` for `target` as `signer` and returns the `BandChanged` events it
+        /// emitted as `(voter, old_band, new_band)`.
+        async fn like_band_changes(
+            client: &mut E2EClient,
+            voting: AccountId,
+            signer: &Keypair,
+            target: AccountId,
+        ) -> Vec<(AccountId, i32, i32)> {
+            let vote = build_message::<VotingRef>(voting).call(|voting| voting.vote(target, TypeVote::Like));
+            let voted = client.call(signer, vote, 0, None).await.expect("vote failed");
+            emitted_by(&voted.events, voting)
+                .into_iter()
+                .filter_map(|event| match event {
+                    Event::BandChanged(BandChanged { voter, old_band, new_band, .. }) => {
+                        Some((voter, old_band, new_band))
+                    }
+                    _ => None,
+                })
+                .collect()
+        }
+
+        #[ink_e2e::test(additional_contracts = "../psp34/Cargo.toml")]
+        async fn band_changed_only_when_band_moves(mut client: ink_e2e::Client<C, E>) -> E2EResult<()> {
+            let (voting, _) = setup(&mut client, Config::default()).await;
+            let charlie = ink_e2e::account_id(Charlie);
+            let dave = ink_e2e::account_id(Dave);
+
+            // Before any vote every voter is in band 1, so the first vote always moves its target.
+            let changes = like_band_changes(&mut client, voting, &ink_e2e::bob(), charlie).await;
+            assert_eq!(changes, vec![(charlie, 1, 3)]);
+            let changes = like_band_changes(&mut client, voting, &ink_e2e::charlie(), dave).await;
+            assert_eq!(changes, vec![(dave, 1, 3)]);
+
+            // Charlie now holds 2 of 5 votes, Bob 2 of 7: only Charlie crosses into a new band.
+            let changes = like_band_changes(&mut client, voting, &ink_e2e::bob(), charlie).await;
+            assert_eq!(changes, vec![(charlie, 1, 2)]);
+            let changes = like_band_changes(&mut client, voting, &ink_e2e::dave(), ink_e2e::account_id(Bob)).await;
+            assert!(changes.is_empty());
+            Ok(())
+        }
+
+        #[ink_e2e::test(additional_contracts = "../psp34/Cargo.toml")]
+        async fn reveal_applies_committed_vote(mut client: ink_e2e::Client<C, E>) -> E2EResult<()> {
+            let config = Config { commit_reveal: true, ..Config::default() };
+            let (voting, _) = setup(&mut client, config).await;
+            let charlie = ink_e2e::account_id(Charlie);
+            let bob = ink_e2e::bob();
+
+            let vote = build_message::<VotingRef>(voting).call(|voting| voting.vote(charlie, TypeVote::Like));
+            let voted = client.call_dry_run(&bob, &vote, 0, None).await.return_value();
+            assert_eq!(voted, Err(Error::CommitRevealRequired));
+
+            let hash = commitment(charlie, TypeVote::Like, [7; 32]);
+            let commit = build_message::<VotingRef>(voting).call(|voting| voting.commit_vote(hash));
+            client.call(&bob, commit, 0, None).await.expect("commit_vote failed");
+
+            for (value, salt) in [(TypeVote::Unlike, [7; 32]), (TypeVote::Like, [8; 32])] {
+                let reveal = build_message::<VotingRef>(voting)
+                    .call(|voting| voting.reveal_vote(charlie, value.clone(), salt));
+                let revealed = client.call_dry_run(&bob, &reveal, 0, None).await.return_value();
+                assert_eq!(revealed, Err(Error::NoCommitment));
+            }
+            let reveal = || {
+                build_message::<VotingRef>(voting).call(|voting| voting.reveal_vote(charlie, TypeVote::Like, [7; 32]))
+            };
+            let revealed = client.call(&bob, reveal(), 0, None).await.expect("reveal_vote failed");
+            assert_eq!(revealed.return_value(), Ok(()));
+            assert_eq!(reputation(&mut client, voting, &ink_e2e::charlie()).await, 1);
+
+            // A commitment is spent by its reveal.
+            let reveal = reveal();
+            let revealed = client.call_dry_run(&bob, &reveal, 0, None).await.return_value();
+            assert_eq!(revealed, Err(Error::NoCommitment));
+            Ok(())
+        }
+
+        #[ink_e2e::test(additional_contracts = "../psp34/Cargo.toml")]
+        async fn my_tokens_lists_vote_badges(mut client: ink_e2e::Client<C, E>) -> E2EResult<()> {
+            let (voting, _) = setup(&mut client, Config::default()).await;
+            let bob = ink_e2e::account_id(Bob);
+            assert!(tokens(&mut client, voting, bob).await.is_empty());
+
+            vote(&mut client, voting, &ink_e2e::bob(), ink_e2e::account_id(Charlie)).await.expect("vote failed");
+            vote(&mut client, voting, &ink_e2e::bob(), ink_e2e::account_id(Dave)).await.expect("vote failed");
+            assert_eq!(tokens(&mut client, voting, bob).await, vec![Id::U8(0), Id::U8(1)]);
+            assert!(tokens(&mut client, voting, ink_e2e::account_id(Charlie)).await.is_empty());
+            Ok(())
+        }
+
+        #[ink_e2e::test(additional_contracts = "../psp34/Cargo.toml")]
+        async fn self_vote_follows_config(mut client: ink_e2e::Client<C, E>) -> E2EResult<()> {
+            let bob = ink_e2e::account_id(Bob);
+            let (voting, _) = setup(&mut client, Config::default()).await;
+            assert_eq!(vote(&mut client, voting, &ink_e2e::bob(), bob).await, Err(Error::NotVoteItSelf));
+
+            let config = Config { allow_self_vote: true, ..Config::default() };
+            let (voting, _) = setup(&mut client, config).await;
+            assert_eq!(vote(&mut client, voting, &ink_e2e::bob(), bob).await, Ok(()));
+            assert_eq!(reputation(&mut client, voting, &ink_e2e::bob()).await, 1);
+            Ok(())
+        }
+
+        #[ink_e2e::test(additional_contracts = "../psp34/Cargo.toml")]
+        async fn stats_track_voters_and_votes(mut client: ink_e2e::Client<C, E>) -> E2EResult<()> {
+            let (voting, _) = setup(&mut client, Config::default()).await;
+            let dave = ink_e2e::account_id(Dave);
+            vote(&mut client, voting, &ink_e2e::bob(), ink_e2e::account_id(Charlie)).await.expect("vote failed");
+            vote(&mut client, voting, &ink_e2e::charlie(), dave).await.expect("vote failed");
+            let remove_voter = build_message::<VotingRef>(voting).call(|voting| voting.remove_voter(dave));
+            client.call(&ink_e2e::alice(), remove_voter, 0, None).await.expect("remove_voter failed");
+
+            let stats = build_message::<VotingRef>(voting).call(|voting| voting.stats());
+            let stats = client.call_dry_run(&ink_e2e::alice(), &stats, 0, None).await.return_value();
+            assert_eq!(
+                stats,
+                Stats {
+                    voter_count: 2,
+                    total_votes: 4,
+                    total_reputation: 4,
+                    nfts_minted: 2,
+                }
+            );
+            Ok(())
+        }
+
+        #[ink_e2e::test(additional_contracts = "../psp34/Cargo.toml")]
+        async fn max_votes_per_target_caps_votes_received(mut client: ink_e2e::Client<C, E>) -> E2EResult<()> {
+            let (voting, _) = setup(&mut client, Config::default()).await;
+            let charlie = ink_e2e::account_id(Charlie);
+            let set_max = build_message::<VotingRef>(voting).call(|voting| voting.set_max_votes_per_target(Some(2)));
+            client.call(&ink_e2e::alice(), set_max, 0, None).await.expect("set_max_votes_per_target failed");
+
+            vote(&mut client, voting, &ink_e2e::bob(), charlie).await.expect("vote failed");
+            vote(&mut client, voting, &ink_e2e::dave(), charlie).await.expect("vote failed");
+            assert_eq!(vote(&mut client, voting, &ink_e2e::bob(), charlie).await, Err(Error::TargetVoteCapReached));
+            assert_eq!(vote(&mut client, voting, &ink_e2e::bob(), ink_e2e::account_id(Dave)).await, Ok(()));
+
+            let set_max = build_message::<VotingRef>(voting).call(|voting| voting.set_max_votes_per_target(None));
+            client.call(&ink_e2e::alice(), set_max, 0, None).await.expect("set_max_votes_per_target failed");
+            assert_eq!(vote(&mut client, voting, &ink_e2e::bob(), charlie).await, Ok(()));
+            Ok(())
+        }
+
+        #[ink_e2e::test(additional_contracts = "../psp34/Cargo.toml")]
+        async fn set_nft_contract_swaps_the_collection(mut client: ink_e2e::Client<C, E>) -> E2EResult<()> {
+            let (voting, collection) = setup(&mut client, Config::default()).await;
+            let code_hash = client.upload("psp34", &ink_e2e::alice(), None).await.expect("psp34 upload failed").code_hash;
+
+            let bogus = build_message::<VotingRef>(voting).call(|voting| voting.set_nft_contract(Hash::from([0; 32]), vec![1]));
+            let result = client.call_dry_run(&ink_e2e::alice(), &bogus, 0, None).await.return_value();
+            assert_eq!(result, Err(Error::NftInstantiationFailed));
+
+            let swap = build_message::<VotingRef>(voting).call(|voting| voting.set_nft_contract(code_hash, vec![1]));
+            let swapped = client.call(&ink_e2e::alice(), swap, 0, None).await.expect("set_nft_contract failed");
+            let replacement = instantiated_by(&swapped.events, voting).expect("no replacement collection");
+            assert_ne!(replacement, collection);
+
+            let bob = ink_e2e::account_id(Bob);
+            vote(&mut client, voting, &ink_e2e::bob(), ink_e2e::account_id(Charlie)).await.expect("vote failed");
+            let old_balance = build_message::<ContractRef>(collection).call(|psp34| psp34.balance(bob));
+            assert_eq!(client.call_dry_run(&ink_e2e::alice(), &old_balance, 0, None).await.return_value(), 0);
+            let new_balance = build_message::<ContractRef>(replacement).call(|psp34| psp34.balance(bob));
+            assert_eq!(client.call_dry_run(&ink_e2e::alice(), &new_balance, 0, None).await.return_value(), 1);
+            Ok(())
+        }
+
+        #[ink_e2e::test(additional_contracts = "../psp34/Cargo.toml")]
+        async fn effective_power_matches_applied_vote(mut client: ink_e2e::Client<C, E>) -> E2EResult<()> {
+            let (voting, _) = setup(&mut client, Config::default()).await;
+            let bob = ink_e2e::account_id(Bob);
+            let adjust = build_message::<VotingRef>(voting).call(|voting| voting.admin_adjust_reputation(bob, 50));
+            client.call(&ink_e2e::alice(), adjust, 0, None).await.expect("admin_adjust_reputation failed");
+
+            let power = build_message::<VotingRef>(voting).call(|voting| voting.effective_power(bob, TypeVote::Like));
+            let power = client.call_dry_run(&ink_e2e::alice(), &power, 0, None).await.return_value();
+            assert_eq!(power, 3);
+            vote(&mut client, voting, &ink_e2e::bob(), ink_e2e::account_id(Charlie)).await.expect("vote failed");
+            assert_eq!(reputation(&mut client, voting, &ink_e2e::charlie()).await, power);
+            Ok(())
+        }
+
+        #[ink_e2e::test(additional_contracts = "../psp34/Cargo.toml")]
+        async fn self_nft_balance_counts_badges_held_by_voting(mut client: ink_e2e::Client<C, E>) -> E2EResult<()> {
+            let (voting, _) = setup(&mut client, Config::default()).await;
+            let balance = build_message::<VotingRef>(voting).call(|voting| voting.self_nft_balance());
+            assert_eq!(client.call_dry_run(&ink_e2e::alice(), &balance, 0, None).await.return_value(), 0);
+
+            let vote = build_message::<VotingRef>(voting)
+                .call(|v| v.vote_with_recipient(ink_e2e::account_id(Charlie), TypeVote::Like, Some(voting)));
+            client.call(&ink_e2e::bob(), vote, 0, None).await.expect("vote_with_recipient failed");
+            assert_eq!(client.call_dry_run(&ink_e2e::alice(), &balance, 0, None).await.return_value(), 1);
+            Ok(())
+        }
+
+        #[ink_e2e::test(additional_contracts = "../psp34/Cargo.toml")]
+        async fn total_votes_is_not_a_vote_topic(mut client: ink_e2e::Client<C, E>) -> E2EResult<()> {
+            let (voting, _) = setup(&mut client, Config::default()).await;
+            let vote = build_message::<VotingRef>(voting).call(|voting| voting.vote(ink_e2e::account_id(Charlie), TypeVote::Like));
+            let voted = client.call(&ink_e2e::bob(), vote, 0, None).await.expect("vote failed");
+
+            let topic_counts: Vec<usize> = voted
+                .events
+                .iter()
+                .filter_map(Result::ok)
+                .filter(|event| event.pallet_name() == "Contracts" && event.variant_name() == "ContractEmitted")
+                .filter(|event| match <(AccountId, Vec<u8>)>::decode(&mut event.field_bytes()) {
+                    Ok((contract, data)) => contract == voting && matches!(Event::decode(&mut &data[..]), Ok(Event::Vote(_))),
+                    Err(_) => false,
+                })
+                .map(|event| event.topics().len())
+                .collect();
+            // Event signature, `voter_id` and `votation`.
+            assert_eq!(topic_counts, vec![3]);
+            Ok(())
+        }
+
+        #[ink_e2e::test(additional_contracts = "../psp34/Cargo.toml")]
+        async fn downvote_damping_applies_to_votes(mut client: ink_e2e::Client<C, E>) -> E2EResult<()> {
+            let config = Config { downvote_damping_window: Some(u64::MAX), ..Config::default() };
+            let (voting, _) = setup(&mut client, config).await;
+            let charlie = ink_e2e::account_id(Charlie);
+            let unlike = || build_message::<VotingRef>(voting).call(|voting| voting.vote(charlie, TypeVote::Unlike));
+            client.call(&ink_e2e::bob(), unlike(), 0, None).await.expect("vote failed");
+            assert_eq!(reputation(&mut client, voting, &ink_e2e::charlie()).await, -1);
+
+            // Dave's power of 1 is halved to nothing as Charlie's second downvoter.
+            client.call(&ink_e2e::dave(), unlike(), 0, None).await.expect("vote failed");
+            assert_eq!(reputation(&mut client, voting, &ink_e2e::charlie()).await, -1);
+            Ok(())
+        }
+
+        #[ink_e2e::test(additional_contracts = "../psp34/Cargo.toml")]
+        async fn vote_and_tip_forwards_value(mut client: ink_e2e::Client<C, E>) -> E2EResult<()> {
+            let (voting, _) = setup(&mut client, Config::default()).await;
+            let charlie = ink_e2e::account_id(Charlie);
+            let before = client.balance(charlie).await.expect("balance failed");
+
+            let tip = build_message::<VotingRef>(voting).call(|voting| voting.vote_and_tip(charlie, TypeVote::Like, 100));
+            let tipped = client.call(&ink_e2e::bob(), tip, 100, None).await.expect("vote_and_tip failed");
+            assert_eq!(tipped.return_value(), Ok(()));
+            assert_eq!(reputation(&mut client, voting, &ink_e2e::charlie()).await, 1);
+            assert_eq!(client.balance(charlie).await.expect("balance failed"), before + 100);
+            Ok(())
+        }
+
+        #[ink_e2e::test(additional_contracts = "../psp34/Cargo.toml ../mock_oracle/Cargo.toml")]
+        async fn oracle_reputation_sets_power(mut client: ink_e2e::Client<C, E>) -> E2EResult<()> {
+            let oracle = client
+                .instantiate("mock_oracle", &ink_e2e::alice(), MockOracleRef::new(80), 0, None)
+                .await
+                .expect("mock_oracle instantiate failed")
+                .account_id;
+            let (voting, _) = setup(&mut client, Config { oracle: Some(oracle), ..Config::default() }).await;
+            let adjust = build_message::<VotingRef>(voting)
+                .call(|voting| voting.admin_adjust_reputation(ink_e2e::account_id(Charlie), 100));
+            client.call(&ink_e2e::alice(), adjust, 0, None).await.expect("admin_adjust_reputation failed");
+
+            // Bob has no local reputation, but the oracle reports 80 out of 100 total votes.
+            let power = build_message::<VotingRef>(voting)
+                .call(|voting| voting.effective_power(ink_e2e::account_id(Bob), TypeVote::Like));
+            assert_eq!(client.call_dry_run(&ink_e2e::alice(), &power, 0, None).await.return_value(), 3);
+
+            vote(&mut client, voting, &ink_e2e::bob(), ink_e2e::account_id(Dave)).await.expect("vote failed");
+            let reputation = build_message::<VotingRef>(voting).call(|voting| voting.get_reputation(ink_e2e::account_id(Dave)));
+            assert_eq!(client.call_dry_run(&ink_e2e::dave(), &reputation, 0, None).await.return_value(), Ok(3));
+            Ok(())
+        }
+
+        #[ink_e2e::test(additional_contracts = "../psp34/Cargo.toml")]
+        async fn unreachable_oracle_falls_back_to_local_power(mut client: ink_e2e::Client<C, E>) -> E2EResult<()> {
+            let oracle = ink_e2e::account_id(ink_e2e::AccountKeyring::Eve);
+            let (voting, _) = setup(&mut client, Config { oracle: Some(oracle), ..Config::default() }).await;
+            let adjust = build_message::<VotingRef>(voting)
+                .call(|voting| voting.admin_adjust_reputation(ink_e2e::account_id(Charlie), 100));
+            client.call(&ink_e2e::alice(), adjust, 0, None).await.expect("admin_adjust_reputation failed");
+
+            let power = build_message::<VotingRef>(voting)
+                .call(|voting| voting.effective_power(ink_e2e::account_id(Bob), TypeVote::Like));
+            assert_eq!(client.call_dry_run(&ink_e2e::alice(), &power, 0, None).await.return_value(), 1);
+            Ok(())
+        }
+
+        #[ink_e2e::test(additional_contracts = "../psp34/Cargo.toml")]
+        async fn transfer_all_ownership_moves_both_owners(mut client: ink_e2e::Client<C, E>) -> E2EResult<()> {
+            let (voting, collection) = setup(&mut client, Config::default()).await;
+            let dave = ink_e2e::account_id(Dave);
+            let transfer = build_message::<VotingRef>(voting).call(|voting| voting.transfer_all_ownership(dave));
+            assert_eq!(client.call(&ink_e2e::alice(), transfer, 0, None).await.expect("transfer_all_ownership failed").return_value(), Ok(()));
+
+            let owner = build_message::<ContractRef>(collection).call(|psp34| psp34.owner());
+            assert_eq!(client.call_dry_run(&ink_e2e::alice(), &owner, 0, None).await.return_value(), Some(dave));
+
+            let add_voter = build_message::<VotingRef>(voting)
+                .call(|voting| voting.add_voter(ink_e2e::account_id(ink_e2e::AccountKeyring::Eve)));
+            let as_alice = client.call_dry_run(&ink_e2e::alice(), &add_voter, 0, None).await.return_value();
+            assert_eq!(as_alice, Err(Error::NotIsAdmin));
+            assert_eq!(client.call_dry_run(&ink_e2e::dave(), &add_voter, 0, None).await.return_value(), Ok(()));
+            Ok(())
+        }
+
+        #[ink_e2e::test(additional_contracts = "../psp34/Cargo.toml")]
+        async fn add_voter_requires_min_holdings(mut client: ink_e2e::Client<C, E>) -> E2EResult<()> {
+            let holdings = client
+                .instantiate("psp34", &ink_e2e::alice(), ContractRef::new(), 0, None)
+                .await
+                .expect("psp34 instantiate failed")
+                .account_id;
+            let bob = ink_e2e::account_id(Bob);
+            let mint = build_message::<ContractRef>(holdings).call(|psp34| psp34.mint_token(bob));
+            client.call(&ink_e2e::alice(), mint, 0, None).await.expect("mint_token failed");
+
+            let config = Config { holdings_collection: Some(holdings), min_holdings: 1, ..Config::default() };
+            let (voting, _) = deploy(&mut client, config).await;
+            let add_bob = build_message::<VotingRef>(voting).call(|voting| voting.add_voter(bob));
+            assert_eq!(client.call_dry_run(&ink_e2e::alice(), &add_bob, 0, None).await.return_value(), Ok(()));
+            let add_charlie = build_message::<VotingRef>(voting).call(|voting| voting.add_voter(ink_e2e::account_id(Charlie)));
+            let result = client.call_dry_run(&ink_e2e::alice(), &add_charlie, 0, None).await.return_value();
+            assert_eq!(result, Err(Error::InsufficientHoldings));
+            Ok(())
+        }
+
+        #[ink_e2e::test(additional_contracts = "../psp34/Cargo.toml")]
+        async fn vote_with_recipient_mints_to_delegate(mut client: ink_e2e::Client<C, E>) -> E2EResult<()> {
+            let (voting, _) = setup(&mut client, Config { recipient_must_be_voter: true, ..Config::default() }).await;
+            let (bob, charlie, dave) = (ink_e2e::account_id(Bob), ink_e2e::account_id(Charlie), ink_e2e::account_id(Dave));
+
+            let to_self = build_message::<VotingRef>(voting).call(|voting| voting.vote_with_recipient(charlie, TypeVote::Like, None));
+            assert_eq!(client.call(&ink_e2e::bob(), to_self, 0, None).await.expect("vote_with_recipient failed").return_value(), Ok(()));
+            let to_dave = build_message::<VotingRef>(voting).call(|voting| voting.vote_with_recipient(dave, TypeVote::Like, Some(dave)));
+            assert_eq!(client.call(&ink_e2e::charlie(), to_dave, 0, None).await.expect("vote_with_recipient failed").return_value(), Ok(()));
+            assert_eq!(tokens(&mut client, voting, bob).await, vec![Id::U8(0)]);
+            assert_eq!(tokens(&mut client, voting, dave).await, vec![Id::U8(1)]);
+            assert!(tokens(&mut client, voting, charlie).await.is_empty());
+
+            let eve = ink_e2e::account_id(ink_e2e::AccountKeyring::Eve);
+            let to_eve = build_message::<VotingRef>(voting).call(|voting| voting.vote_with_recipient(bob, TypeVote::Like, Some(eve)));
+            let result = client.call_dry_run(&ink_e2e::dave(), &to_eve, 0, None).await.return_value();
+            assert_eq!(result, Err(Error::RecipientNotVoter));
+            Ok(())
+        }
+
+        #[ink_e2e::test(additional_contracts = "../psp34/Cargo.toml")]
+        async fn mint_to_target_rewards_the_target(mut client: ink_e2e::Client<C, E>) -> E2EResult<()> {
+            let (voting, _) = setup(&mut client, Config { mint_to_target: true, ..Config::default() }).await;
+            let (bob, charlie) = (ink_e2e::account_id(Bob), ink_e2e::account_id(Charlie));
+            vote(&mut client, voting, &ink_e2e::bob(), charlie).await.expect("vote failed");
+            assert_eq!(tokens(&mut client, voting, charlie).await, vec![Id::U8(0)]);
+            assert!(tokens(&mut client, voting, bob).await.is_empty());
+            Ok(())
+        }
+
+        #[ink_e2e::test(additional_contracts = "../psp34/Cargo.toml")]
+        async fn default_mode_rewards_the_voter(mut client: ink_e2e::Client<C, E>) -> E2EResult<()> {
+            let (voting, _) = setup(&mut client, Config::default()).await;
+            let (bob, charlie) = (ink_e2e::account_id(Bob), ink_e2e::account_id(Charlie));
+            vote(&mut client, voting, &ink_e2e::bob(), charlie).await.expect("vote failed");
+            assert_eq!(tokens(&mut client, voting, bob).await, vec![Id::U8(0)]);
+            assert!(tokens(&mut client, voting, charlie).await.is_empty());
+            Ok(())
+        }
+
+        #[ink_e2e::test(additional_contracts = "../psp34/Cargo.toml")]
+        async fn votes_per_mint_mints_on_every_third_vote(mut client: ink_e2e::Client<C, E>) -> E2EResult<()> {
+            let (voting, _) = setup(&mut client, Config { votes_per_mint: 3, ..Config::default() }).await;
+            let (bob, charlie, dave) = (ink_e2e::account_id(Bob), ink_e2e::account_id(Charlie), ink_e2e::account_id(Dave));
+
+            vote(&mut client, voting, &ink_e2e::bob(), charlie).await.expect("vote failed");
+            vote(&mut client, voting, &ink_e2e::bob(), dave).await.expect("vote failed");
+            assert!(tokens(&mut client, voting, bob).await.is_empty());
+            vote(&mut client, voting, &ink_e2e::bob(), charlie).await.expect("vote failed");
+            assert_eq!(tokens(&mut client, voting, bob).await, vec![Id::U8(0)]);
+            Ok(())
+        }
+
+        #[ink_e2e::test(additional_contracts = "../psp34/Cargo.toml")]
+        async fn last_minted_id_matches_the_collection(mut client: ink_e2e::Client<C, E>) -> E2EResult<()> {
+            let (voting, collection) = setup(&mut client, Config::default()).await;
+            let (bob, charlie) = (ink_e2e::account_id(Bob), ink_e2e::account_id(Charlie));
+            let last_minted = build_message::<VotingRef>(voting).call(|voting| voting.last_minted_id(bob));
+            assert_eq!(client.call_dry_run(&ink_e2e::alice(), &last_minted, 0, None).await.return_value(), None);
+
+            vote(&mut client, voting, &ink_e2e::charlie(), bob).await.expect("vote failed");
+            vote(&mut client, voting, &ink_e2e::bob(), charlie).await.expect("vote failed");
+            let owned = build_message::<ContractRef>(collection).call(|psp34| psp34.token_by_index(bob, 0));
+            let owned = client.call_dry_run(&ink_e2e::alice(), &owned, 0, None).await.return_value();
+            assert_eq!(owned, Some(Id::U8(1)));
+            let last_minted = client.call_dry_run(&ink_e2e::alice(), &last_minted, 0, None).await.return_value();
+            assert_eq!(last_minted, owned);
+            Ok(())
+        }
+
+        #[ink_e2e::test(additional_contracts = "../psp34/Cargo.toml")]
+        async fn soulbound_badges_still_mint(mut client: ink_e2e::Client<C, E>) -> E2EResult<()> {
+            let (voting, _) = setup(&mut client, Config { soulbound_badges: true, ..Config::default() }).await;
+            vote(&mut client, voting, &ink_e2e::bob(), ink_e2e::account_id(Charlie)).await.expect("vote failed");
+            assert_eq!(tokens(&mut client, voting, ink_e2e::account_id(Bob)).await, vec![Id::U8(0)]);
+            Ok(())
+        }
+
+        #[ink_e2e::test(additional_contracts = "../psp34/Cargo.toml")]
+        async fn claim_pending_mints_skips_delivered_badges(mut client: ink_e2e::Client<C, E>) -> E2EResult<()> {
+            let (voting, _) = setup(&mut client, Config::default()).await;
+            vote(&mut client, voting, &ink_e2e::bob(), ink_e2e::account_id(Charlie)).await.expect("vote failed");
+            vote(&mut client, voting, &ink_e2e::bob(), ink_e2e::account_id(Dave)).await.expect("vote failed");
+
+            let claim = build_message::<VotingRef>(voting).call(|voting| voting.claim_pending_mints());
+            let claimed = client.call(&ink_e2e::bob(), claim, 0, None).await.expect("claim_pending_mints failed");
+            assert_eq!(claimed.return_value(), Ok(0));
+            assert_eq!(tokens(&mut client, voting, ink_e2e::account_id(Bob)).await.len(), 2);
+            Ok(())
+        }
+
+        #[ink_e2e::test(additional_contracts = "../psp34/Cargo.toml")]
+        async fn claim_pending_mints_delivers_badges_earned_while_disabled(mut client: ink_e2e::Client<C, E>) -> E2EResult<()> {
+            let (voting, _) = setup(&mut client, Config { mint_enabled: false, ..Config::default() }).await;
+            let bob = ink_e2e::account_id(Bob);
+            vote(&mut client, voting, &ink_e2e::bob(), ink_e2e::account_id(Charlie)).await.expect("vote failed");
+            vote(&mut client, voting, &ink_e2e::bob(), ink_e2e::account_id(Dave)).await.expect("vote failed");
+            assert!(tokens(&mut client, voting, bob).await.is_empty());
+
+            let enable = build_message::<VotingRef>(voting).call(|voting| voting.set_mint_enabled(true));
+            let enabled = client.call(&ink_e2e::alice(), enable, 0, None).await.expect("set_mint_enabled failed");
+            assert_eq!(enabled.return_value(), Ok(()));
+            let claim = build_message::<VotingRef>(voting).call(|voting| voting.claim_pending_mints());
+            let claimed = client.call(&ink_e2e::bob(), claim, 0, None).await.expect("claim_pending_mints failed");
+            assert_eq!(claimed.return_value(), Ok(2));
+            assert_eq!(tokens(&mut client, voting, bob).await, vec![Id::U8(0), Id::U8(1)]);
+            Ok(())
+        }
+
+        #[ink_e2e::test(additional_contracts = "../psp34/Cargo.toml")]
+        async fn get_balance_through_votingtraits(mut client: ink_e2e::Client<C, E>) -> E2EResult<()> {
+            let (voting, _) = setup(&mut client, Config::default()).await;
+            let bob = ink_e2e::account_id(Bob);
+            vote(&mut client, voting, &ink_e2e::bob(), ink_e2e::account_id(Charlie)).await.expect("vote failed");
+
+            let balance = build_message::<VotingRef>(voting).call(|voting| Votingtraits::get_balance(voting, bob));
+            assert_eq!(client.call_dry_run(&ink_e2e::bob(), &balance, 0, None).await.return_value(), Ok(1));
+            let result = client.call_dry_run(&ink_e2e::charlie(), &balance, 0, None).await.return_value();
+            assert_eq!(result, Err(Error::MustBeItSelf));
+            Ok(())
+        }
+
+        #[ink_e2e::test(additional_contracts = "../psp34/Cargo.toml")]
+        async fn vote_mints_ignores_direct_mints(mut client: ink_e2e::Client<C, E>) -> E2EResult<()> {
+            let (voting, collection) = setup(&mut client, Config::default()).await;
+            let alice = ink_e2e::account_id(Alice);
+            vote(&mut client, voting, &ink_e2e::bob(), ink_e2e::account_id(Charlie)).await.expect("vote failed");
+            vote(&mut client, voting, &ink_e2e::charlie(), ink_e2e::account_id(Dave)).await.expect("vote failed");
+
+            let allow = build_message::<VotingRef>(voting).call(|voting| voting.set_nft_minter(alice, true));
+            assert_eq!(client.call(&ink_e2e::alice(), allow, 0, None).await.expect("set_nft_minter failed").return_value(), Ok(()));
+            let mint = build_message::<ContractRef>(collection).call(|psp34| psp34.mint_token(alice));
+            client.call(&ink_e2e::alice(), mint, 0, None).await.expect("mint_token failed");
+
+            let vote_mints = build_message::<VotingRef>(voting).call(|voting| voting.vote_mints());
+            assert_eq!(client.call_dry_run(&ink_e2e::alice(), &vote_mints, 0, None).await.return_value(), 2);
+            let total = build_message::<ContractRef>(collection).call(|psp34| psp34.total_minted());
+            assert_eq!(client.call_dry_run(&ink_e2e::alice(), &total, 0, None).await.return_value(), 3);
+            Ok(())
+        }
+
+        #[ink_e2e::test(additional_contracts = "../psp34/Cargo.toml")]
+        async fn redeem_reputation_mints_a_premium_badge(mut client: ink_e2e::Client<C, E>) -> E2EResult<()> {
+            let (voting, _) = setup(&mut client, Config::default()).await;
+            let bob = ink_e2e::account_id(Bob);
+            let adjust = build_message::<VotingRef>(voting).call(|voting| voting.admin_adjust_reputation(bob, 10));
+            client.call(&ink_e2e::alice(), adjust, 0, None).await.expect("admin_adjust_reputation failed");
+
+            let too_much = build_message::<VotingRef>(voting).call(|voting| voting.redeem_reputation(11));
+            let result = client.call_dry_run(&ink_e2e::bob(), &too_much, 0, None).await.return_value();
+            assert_eq!(result, Err(Error::InsufficientReputation));
+
+            let redeem = build_message::<VotingRef>(voting).call(|voting| voting.redeem_reputation(4));
+            assert_eq!(client.call(&ink_e2e::bob(), redeem, 0, None).await.expect("redeem_reputation failed").return_value(), Ok(Id::U16(0)));
+            let reputation = build_message::<VotingRef>(voting).call(|voting| voting.get_reputation(bob));
+            assert_eq!(client.call_dry_run(&ink_e2e::bob(), &reputation, 0, None).await.return_value(), Ok(6));
+            assert_eq!(tokens(&mut client, voting, bob).await, vec![Id::U16(0)]);
+            Ok(())
+        }
+
+        #[ink_e2e::test(additional_contracts = "../psp34/Cargo.toml")]
+        async fn simulate_vote_predicts_the_mint(mut client: ink_e2e::Client<C, E>) -> E2EResult<()> {
+            let (voting, _) = setup(&mut client, Config { votes_per_mint: 2, ..Config::default() }).await;
+            let (bob, charlie) = (ink_e2e::account_id(Bob), ink_e2e::account_id(Charlie));
+            let simulate = build_message::<VotingRef>(voting).call(|voting| voting.simulate_vote(charlie, TypeVote::Like));
+
+            let sim = client.call_dry_run(&ink_e2e::bob(), &simulate, 0, None).await.return_value();
+            assert_eq!(sim, SimResult { new_reputation: 1, would_mint: false, error: None });
+            vote(&mut client, voting, &ink_e2e::bob(), charlie).await.expect("vote failed");
+            assert!(tokens(&mut client, voting, bob).await.is_empty());
+
+            let sim = client.call_dry_run(&ink_e2e::bob(), &simulate, 0, None).await.return_value();
+            assert_eq!(sim, SimResult { new_reputation: 2, would_mint: true, error: None });
+            vote(&mut client, voting, &ink_e2e::bob(), charlie).await.expect("vote failed");
+            assert_eq!(tokens(&mut client, voting, bob).await, vec![Id::U8(0)]);
+            Ok(())
+        }
+
+        #[ink_e2e::test(additional_contracts = "../psp34/Cargo.toml")]
+        async fn simulate_vote_reports_no_mint_when_minting_would_fail(mut client: ink_e2e::Client<C, E>) -> E2EResult<()> {
+            let (voting, collection) = setup(&mut client, Config::default()).await;
+            let dave = ink_e2e::account_id(Dave);
+            let transfer = build_message::<VotingRef>(voting).call(|voting| voting.transfer_all_ownership(dave));
+            client.call(&ink_e2e::alice(), transfer, 0, None).await.expect("transfer_all_ownership failed");
+            let revoke = build_message::<ContractRef>(collection).call(|psp34| psp34.set_minter(voting, false));
+            client.call(&ink_e2e::dave(), revoke, 0, None).await.expect("set_minter failed");
+
+            let charlie = ink_e2e::account_id(Charlie);
+            let simulate = build_message::<VotingRef>(voting).call(|voting| voting.simulate_vote(charlie, TypeVote::Like));
+            let sim = client.call_dry_run(&ink_e2e::bob(), &simulate, 0, None).await.return_value();
+            assert_eq!(sim, SimResult { new_reputation: 1, would_mint: false, error: None });
+            let result = vote(&mut client, voting, &ink_e2e::bob(), charlie).await;
+            assert_eq!(result, Err(Error::NftNotMint));
+            Ok(())
+        }
+
+        #[ink_e2e::test(additional_contracts = "../psp34/Cargo.toml ../mock_oracle/Cargo.toml")]
+        async fn cooldown_scales_with_oracle_band(mut client: ink_e2e::Client<C, E>) -> E2EResult<()> {
+            let oracle = client
+                .instantiate("mock_oracle", &ink_e2e::alice(), MockOracleRef::new(80), 0, None)
+                .await
+                .expect("mock_oracle instantiate failed")
+                .account_id;
+            let config = Config {
+                oracle: Some(oracle),
+                cooldown_secs: 10,
+                cooldown_scales_with_band: true,
+                ..Config::default()
+            };
+            let (voting, _) = setup(&mut client, config).await;
+            let adjust = build_message::<VotingRef>(voting)
+                .call(|voting| voting.admin_adjust_reputation(ink_e2e::account_id(Charlie), 100));
+            client.call(&ink_e2e::alice(), adjust, 0, None).await.expect("admin_adjust_reputation failed");
+
+            // Bob has no local reputation, but the oracle puts Bob in band 3: a 30 second cooldown.
+            vote(&mut client, voting, &ink_e2e::bob(), ink_e2e::account_id(Dave)).await.expect("vote failed");
+            let remaining = build_message::<VotingRef>(voting)
+                .call(|voting| voting.cooldown_remaining(ink_e2e::account_id(Bob)));
+            assert!(client.call_dry_run(&ink_e2e::alice(), &remaining, 0, None).await.return_value() > 20);
+            Ok(())
+        }
+
+        #[ink_e2e::test(additional_contracts = "../psp34/Cargo.toml")]
+        async fn set_nft_contract_locks_after_first_mint(mut client: ink_e2e::Client<C, E>) -> E2EResult<()> {
+            let (voting, _) = setup(&mut client, Config::default()).await;
+            let code_hash = client.upload("psp34", &ink_e2e::alice(), None).await.expect("psp34 upload failed").code_hash;
+            let before = build_message::<VotingRef>(voting).call(|voting| voting.set_nft_contract(code_hash, vec![1]));
+            assert_eq!(client.call(&ink_e2e::alice(), before, 0, None).await.expect("set_nft_contract failed").return_value(), Ok(()));
+
+            vote(&mut client, voting, &ink_e2e::bob(), ink_e2e::account_id(Charlie)).await.expect("vote failed");
+            let after = build_message::<VotingRef>(voting).call(|voting| voting.set_nft_contract(code_hash, vec![2]));
+            let result = client.call_dry_run(&ink_e2e::alice(), &after, 0, None).await.return_value();
+            assert_eq!(result, Err(Error::NftContractLocked));
+            Ok(())
+        }
+
+        #[ink_e2e::test(additional_contracts = "../psp34/Cargo.toml")]
+        async fn allow_nft_contract_swap_lifts_the_lock(mut client: ink_e2e::Client<C, E>) -> E2EResult<()> {
+            let (voting, _) = setup(&mut client, Config { allow_nft_contract_swap: true, ..Config::default() }).await;
+            let code_hash = client.upload("psp34", &ink_e2e::alice(), None).await.expect("psp34 upload failed").code_hash;
+            vote(&mut client, voting, &ink_e2e::bob(), ink_e2e::account_id(Charlie)).await.expect("vote failed");
+
+            let swap = build_message::<VotingRef>(voting).call(|voting| voting.set_nft_contract(code_hash, vec![3]));
+            assert_eq!(client.call(&ink_e2e::alice(), swap, 0, None).await.expect("set_nft_contract failed").return_value(), Ok(()));
+            Ok(())
+        }
+
+        #[ink_e2e::test(additional_contracts = "../psp34/Cargo.toml")]
+        async fn voting_keeps_minting_after_ownership_transfer(mut client: ink_e2e::Client<C, E>) -> E2EResult<()> {
+            let (voting, _) = setup(&mut client, Config::default()).await;
+            let dave = ink_e2e::account_id(Dave);
+            let transfer = build_message::<VotingRef>(voting).call(|voting| voting.transfer_all_ownership(dave));
+            assert_eq!(client.call(&ink_e2e::alice(), transfer, 0, None).await.expect("transfer_all_ownership failed").return_value(), Ok(()));
+
+            vote(&mut client, voting, &ink_e2e::bob(), ink_e2e::account_id(Charlie)).await.expect("vote failed");
+            assert_eq!(tokens(&mut client, voting, ink_e2e::account_id(Bob)).await, vec![Id::U8(0)]);
+
+            let allow = build_message::<VotingRef>(voting).call(|voting| voting.set_nft_minter(dave, true));
+            let result = client.call_dry_run(&ink_e2e::dave(), &allow, 0, None).await.return_value();
+            assert_eq!(result, Err(Error::SetMinterFailed));
+            Ok(())
         }
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,7 @@
+use ink::primitives::AccountId;
+
+#[ink::trait_definition]
+pub trait ReputationOracle {
+    #[ink(message)]
+    fn reputation_of(&self, who: AccountId) -> i32;
+}
\ No newline at end of file
@@ -7,6 +7,15 @@ pub trait Votingtraits {
     #[ink(message)]
     fn vote(&mut self, voter_id: AccountId, value: TypeVote) -> Result<(), Error>;
 
+    #[ink(message)]
+    fn add_voter(&mut self, voter_id: AccountId) -> Result<(), Error>;
+
+    #[ink(message)]
+    fn remove_voter(&mut self, voter_id: AccountId) -> Result<(), Error>;
+
     #[ink(message)]
     fn get_reputation(&self, voter_id: AccountId) -> Result<i32, Error>;
+
+    #[ink(message)]
+    fn get_balance(&self, voter_id: AccountId) -> Result<u32, Error>;
 }
\ No newline at end of file
@@ -1,5 +1,5 @@
 #![cfg_attr(not(feature = "std"), no_std, no_main)]
-#[cfg_attr(feature = "cargo-clippy", allow(clippy::new_without_default))]
+#[allow(clippy::new_without_default)]
 #[ink::contract]
 mod mapper { // Mapping + Voter
     use ink::storage::Mapping;//, env::call::ConstructorReturnType};
@@ -15,6 +15,7 @@ mod mapper { // Mapping + Voter
         VoterNotExist,
         NotVoteItself,
         NotIsVoter,
+        Overflow,
     }
 
     #[ink(event)]
@@ -45,9 +46,9 @@ mod mapper { // Mapping + Voter
     #[ink(storage)]
     pub struct Mapper {
         admin: Admin,
-        votes: Mapping<AccountId, u32>,
+        votes: Mapping<AccountId, u128>,
         enabled_voters: Mapping<AccountId, ()>,
-        total_votes: u32,
+        total_votes: u128,
     }
 
     impl Mapper {
@@ -94,7 +95,7 @@ mod mapper { // Mapping + Voter
         }
 
         #[ink(message)]
-        pub fn get_reputation(&mut self, voter_id: AccountId) -> Result<u32, Error> {
+        pub fn get_reputation(&mut self, voter_id: AccountId) -> Result<u128, Error> {
             if self.env().caller() != voter_id {
                 return Err(Error::MustBeItSelf);
             }
@@ -118,27 +119,90 @@ mod mapper { // Mapping + Voter
 
             let caller = self.env().caller();
             let caller_votes =self.votes.get(caller).unwrap_or(0);
-            let power = self.power_of_vote(caller_votes);            
+            let power = self.power_of_vote(caller_votes)?;
             
             let voter_votes = self.votes.get(voter_id).unwrap_or(0);
-            self.votes.insert(voter_id, &(voter_votes + power));
+            let new_votes = voter_votes.checked_add(power).ok_or(Error::Overflow)?;
+            let total_votes = self.total_votes.checked_add(power).ok_or(Error::Overflow)?;
+            self.votes.insert(voter_id, &new_votes);
 
-            self.total_votes += power;
+            self.total_votes = total_votes;
             self.env().emit_event(Vote { voter_id });
             Ok(())
         }
 
-        fn power_of_vote(&mut self, votes: u32) -> u32 {
-            if self.total_votes == 0 {
-                1
-            } else {
-                let power = (votes * 100)/self.total_votes;
-                match power {
+        fn power_of_vote(&mut self, votes: u128) -> Result<u128, Error> {
+            let scaled = votes.checked_mul(100).ok_or(Error::Overflow)?;
+            match scaled.checked_div(self.total_votes) {
+                None => Ok(1),
+                Some(power) => Ok(match power {
                     0...33 => 1,
                     34...66 => 2,
                     _ => 3
-                }
+                }),
             }
         }
     }
-}
\ No newline at end of file
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        type Accounts = ink::env::test::DefaultAccounts<ink::env::DefaultEnvironment>;
+
+        fn setup() -> (Mapper, Accounts) {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+            let mut mapper = Mapper::new(accounts.alice);
+            mapper.add_voter(accounts.bob).unwrap();
+            mapper.add_voter(accounts.charlie).unwrap();
+            (mapper, accounts)
+        }
+
+        #[ink::test]
+        fn vote_past_u32_max_succeeds() {
+            let (mut mapper, accounts) = setup();
+            let near = u128::from(u32::MAX);
+            mapper.votes.insert(accounts.charlie, &near);
+            mapper.total_votes = near;
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            assert_eq!(mapper.vote(accounts.charlie), Ok(()));
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.charlie);
+            assert_eq!(mapper.get_reputation(accounts.charlie), Ok(near + 1));
+            assert_eq!(mapper.total_votes, near + 1);
+        }
+
+        #[ink::test]
+        fn vote_overflowing_target_reputation_fails() {
+            let (mut mapper, accounts) = setup();
+            mapper.votes.insert(accounts.charlie, &u128::MAX);
+            mapper.total_votes = 1;
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            assert_eq!(mapper.vote(accounts.charlie), Err(Error::Overflow));
+            assert_eq!(mapper.total_votes, 1);
+        }
+
+        #[ink::test]
+        fn vote_overflowing_total_votes_fails() {
+            let (mut mapper, accounts) = setup();
+            mapper.total_votes = u128::MAX;
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            assert_eq!(mapper.vote(accounts.charlie), Err(Error::Overflow));
+            assert_eq!(mapper.votes.get(accounts.charlie), None);
+        }
+
+        #[ink::test]
+        fn vote_with_unscalable_caller_reputation_fails() {
+            let (mut mapper, accounts) = setup();
+            mapper.votes.insert(accounts.bob, &(u128::MAX / 100 + 1));
+            mapper.total_votes = 1;
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            assert_eq!(mapper.vote(accounts.charlie), Err(Error::Overflow));
+        }
+    }
+}